@@ -1,21 +1,38 @@
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState, UntypedAssetId},
+    prelude::*,
+    utils::HashMap,
+};
+use derive_more::derive::{Display, Error, From};
+use serde::Deserialize;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HandleMap<ImageKey>>();
     app.init_resource::<HandleMap<ImageKey>>();
 
+    app.init_asset::<AudioManifest>();
+    app.init_asset_loader::<AudioManifestLoader>();
+    app.init_resource::<AudioManifestHandle>();
+
+    // Filled in from the audio manifest once it's loaded
     app.register_type::<HandleMap<SfxKey>>();
-    app.init_resource::<HandleMap<SfxKey>>();
+    app.insert_resource(HandleMap::<SfxKey>::from(HashMap::new()));
 
     app.register_type::<HandleMap<SoundtrackKey>>();
-    app.init_resource::<HandleMap<SoundtrackKey>>();
+    app.insert_resource(HandleMap::<SoundtrackKey>::from(HashMap::new()));
+
+    app.add_systems(PreUpdate, update_audio_handles);
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+/// Where the audio manifest is, under `assets`
+static AUDIO_MANIFEST_PATH: &str = "audio/manifest.ron";
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect)]
 pub enum ImageKey {}
 
 impl AssetKey for ImageKey {
     type Asset = Image;
+    const ALL: &'static [Self] = &[];
 }
 
 impl FromWorld for HandleMap<ImageKey> {
@@ -25,7 +42,7 @@ impl FromWorld for HandleMap<ImageKey> {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Deserialize)]
 pub enum SfxKey {
     ButtonHover,
     ButtonPress,
@@ -34,30 +51,10 @@ pub enum SfxKey {
 
 impl AssetKey for SfxKey {
     type Asset = AudioSource;
+    const ALL: &'static [Self] = &[Self::ButtonHover, Self::ButtonPress, Self::Thunder];
 }
 
-impl FromWorld for HandleMap<SfxKey> {
-    fn from_world(world: &mut World) -> Self {
-        let asset_server = world.resource::<AssetServer>();
-        [
-            (
-                SfxKey::ButtonHover,
-                asset_server.load("audio/sfx/button_hover.ogg"),
-            ),
-            (
-                SfxKey::ButtonPress,
-                asset_server.load("audio/sfx/button_press.ogg"),
-            ),
-            (
-                SfxKey::Thunder,
-                asset_server.load("audio/sfx/emulius_thunder-01.ogg"),
-            ),
-        ]
-        .into()
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect, Deserialize)]
 pub enum SoundtrackKey {
     Credits,
     Gameplay,
@@ -65,27 +62,13 @@ pub enum SoundtrackKey {
 
 impl AssetKey for SoundtrackKey {
     type Asset = AudioSource;
+    const ALL: &'static [Self] = &[Self::Credits, Self::Gameplay];
 }
 
-impl FromWorld for HandleMap<SoundtrackKey> {
-    fn from_world(world: &mut World) -> Self {
-        let asset_server = world.resource::<AssetServer>();
-        [
-            (
-                SoundtrackKey::Credits,
-                asset_server.load("audio/soundtracks/Monkeys Spinning Monkeys.ogg"),
-            ),
-            (
-                SoundtrackKey::Gameplay,
-                asset_server.load("audio/soundtracks/Kevin MacLeod - Clean Soul.mp3"),
-            ),
-        ]
-        .into()
-    }
-}
-
-pub trait AssetKey: Sized {
+pub trait AssetKey: Sized + 'static {
     type Asset: Asset;
+    /// Every key, so we can tell if any are missing a handle
+    const ALL: &'static [Self];
 }
 
 #[derive(Resource, Reflect, Deref, DerefMut)]
@@ -101,9 +84,135 @@ where
     }
 }
 
-impl<K: AssetKey> HandleMap<K> {
-    pub fn all_loaded(&self, asset_server: &AssetServer) -> bool {
+impl<K: AssetKey + Eq + std::hash::Hash> HandleMap<K> {
+    /// Keys there's no handle for at all
+    pub fn missing_keys(&self) -> impl Iterator<Item = &'static K> + '_ {
+        K::ALL.iter().filter(|key| !self.contains_key(*key))
+    }
+
+    /// Count how many of the handles have loaded, failed or are still loading
+    pub fn tally(&self, asset_server: &AssetServer, progress: &mut LoadProgress) {
+        for handle in self.values() {
+            progress.count(asset_server, handle);
+        }
+    }
+
+    /// A description of every asset that failed to load
+    pub fn load_errors(&self, asset_server: &AssetServer) -> Vec<String> {
         self.values()
-            .all(|x| asset_server.is_loaded_with_dependencies(x))
+            .filter_map(|handle| match asset_server.get_load_state(handle) {
+                Some(LoadState::Failed(error)) => Some(error.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// How many of a set of assets have loaded, failed or are still on their way
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub failed: usize,
+    pub pending: usize,
+}
+
+impl LoadProgress {
+    pub fn count(&mut self, asset_server: &AssetServer, id: impl Into<UntypedAssetId>) {
+        match asset_server.get_load_state(id) {
+            Some(LoadState::Loaded) => self.loaded += 1,
+            Some(LoadState::Failed(_)) => self.failed += 1,
+            _ => self.pending += 1,
+        }
+    }
+
+    pub const fn total(&self) -> usize {
+        self.loaded + self.failed + self.pending
+    }
+}
+
+/// The audio files to load for each key, read from `assets/audio/manifest.ron`
+#[derive(Asset, TypePath, Debug)]
+pub struct AudioManifest {
+    sfx: HashMap<SfxKey, Handle<AudioSource>>,
+    soundtracks: HashMap<SoundtrackKey, Handle<AudioSource>>,
+}
+
+/// The manifest as it's written, with paths rather than handles
+#[derive(Deserialize)]
+struct AudioManifestFile {
+    sfx: HashMap<SfxKey, String>,
+    soundtracks: HashMap<SoundtrackKey, String>,
+}
+
+#[derive(Debug, Display, Error, From)]
+pub enum AudioManifestError {
+    #[display("couldn't read the audio manifest: {_0}")]
+    Io(std::io::Error),
+    #[display("couldn't parse the audio manifest: {_0}")]
+    Ron(ron::error::SpannedError),
+}
+
+#[derive(Default)]
+struct AudioManifestLoader;
+
+impl AssetLoader for AudioManifestLoader {
+    type Asset = AudioManifest;
+    type Settings = ();
+    type Error = AudioManifestError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<AudioManifest, AudioManifestError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: AudioManifestFile = ron::de::from_bytes(&bytes)?;
+        Ok(AudioManifest {
+            sfx: file
+                .sfx
+                .into_iter()
+                .map(|(key, path)| (key, load_context.load(path)))
+                .collect(),
+            soundtracks: file
+                .soundtracks
+                .into_iter()
+                .map(|(key, path)| (key, load_context.load(path)))
+                .collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct AudioManifestHandle(pub Handle<AudioManifest>);
+
+impl FromWorld for AudioManifestHandle {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self(asset_server.load(AUDIO_MANIFEST_PATH))
+    }
+}
+
+/// Fill in the audio handles once the manifest has loaded, and again whenever it's edited
+fn update_audio_handles(
+    mut events: EventReader<AssetEvent<AudioManifest>>,
+    manifests: Res<Assets<AudioManifest>>,
+    mut sfx_handles: ResMut<HandleMap<SfxKey>>,
+    mut soundtrack_handles: ResMut<HandleMap<SoundtrackKey>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let Some(manifest) = manifests.get(*id) else {
+            continue;
+        };
+        sfx_handles.0.clone_from(&manifest.sfx);
+        soundtrack_handles.0.clone_from(&manifest.soundtracks);
     }
 }
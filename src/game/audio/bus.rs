@@ -0,0 +1,187 @@
+//! Music and sound effects each play through their own bus, so they can be turned up and down
+//! separately, including while they're playing.
+
+use bevy::{audio::AudioSinkPlayback, prelude::*};
+
+use crate::game::settings::Settings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MusicDucking>();
+    app.observe(duck_music);
+    app.add_systems(
+        Update,
+        (
+            (update_ducking, update_faders),
+            (
+                apply_bus_volume::<AudioSink>,
+                apply_bus_volume::<SpatialAudioSink>,
+            ),
+        )
+            .chain(),
+    );
+}
+
+/// The loudest a single sound effect is allowed to play before the bus gain
+static SFX_LIMIT: f32 = 1.;
+/// How far the music is turned down while ducked
+static DUCKED_GAIN: f32 = 0.35;
+/// Seconds the music stays ducked after the last event that ducked it
+static DUCK_HOLD: f32 = 2.;
+/// How quickly the music is ducked and brought back up, in gain per second
+static DUCK_ATTACK: f32 = 4.;
+static DUCK_RELEASE: f32 = 0.5;
+
+/// The bus a sound plays through
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBus {
+    Music,
+    Sfx,
+}
+
+impl AudioBus {
+    /// The gain the bus currently applies on top of each sound's own volume
+    pub fn gain(self, settings: &Settings, ducking: &MusicDucking) -> f32 {
+        match self {
+            Self::Music => settings.music_volume * ducking.gain,
+            Self::Sfx => settings.sfx_volume,
+        }
+    }
+
+    /// The volume a sound plays at through this bus, before the global volume
+    pub fn volume(self, source: f32, settings: &Settings, ducking: &MusicDucking) -> f32 {
+        let source = match self {
+            Self::Music => source,
+            Self::Sfx => source.min(SFX_LIMIT),
+        };
+        source * self.gain(settings, ducking)
+    }
+}
+
+/// How loud a sound is before its bus is applied
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SourceVolume(pub f32);
+
+/// Fades a sound in or out over time, on top of its source volume
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Fader {
+    gain: f32,
+    target: f32,
+    /// Change in gain per second
+    speed: f32,
+    /// Whether to get rid of the sound once it's faded out
+    despawn: bool,
+}
+
+impl Fader {
+    /// Start silent and fade up to `target` over `duration` seconds
+    pub const fn fade_in(target: f32, duration: f32) -> Self {
+        let mut fader = Self {
+            gain: 0.,
+            target: 0.,
+            speed: 0.,
+            despawn: false,
+        };
+        fader.fade_to(target, duration);
+        fader
+    }
+
+    pub const fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Head towards `target`, taking `duration` seconds to cover the full range
+    pub const fn fade_to(&mut self, target: f32, duration: f32) {
+        self.target = target;
+        self.speed = duration.recip();
+    }
+
+    /// Fade to silence over `duration` seconds, then despawn the sound
+    pub const fn fade_out(&mut self, duration: f32) {
+        self.fade_to(0., duration);
+        self.despawn = true;
+    }
+}
+
+/// Trigger this event when something loud happens to briefly turn the music down under it
+#[derive(Event, Debug)]
+pub struct DuckMusic;
+
+/// The gain ducking currently applies to the music bus
+#[derive(Resource, Debug)]
+pub struct MusicDucking {
+    gain: f32,
+    hold: f32,
+}
+
+impl Default for MusicDucking {
+    fn default() -> Self {
+        Self { gain: 1., hold: 0. }
+    }
+}
+
+fn duck_music(_trigger: Trigger<DuckMusic>, mut ducking: ResMut<MusicDucking>) {
+    ducking.hold = DUCK_HOLD;
+}
+
+/// Ducking runs on real time so the music still comes back up while the game is paused
+fn update_ducking(time: Res<Time<Real>>, mut ducking: ResMut<MusicDucking>) {
+    let delta = time.delta_seconds();
+    let (target, rate) = if ducking.hold > 0. {
+        (DUCKED_GAIN, DUCK_ATTACK)
+    } else {
+        (1., DUCK_RELEASE)
+    };
+    let gain = approach(ducking.gain, target, rate * delta);
+    // Only touch the resource when something changes, so the buses aren't updated every frame
+    if ducking.hold > 0. {
+        ducking.hold = (ducking.hold - delta).max(0.);
+    }
+    if (gain - ducking.gain).abs() > f32::EPSILON {
+        ducking.gain = gain;
+    }
+}
+
+/// Faders also run on real time, so pausing doesn't leave two tracks playing over each other
+fn update_faders(
+    time: Res<Time<Real>>,
+    mut fader_query: Query<(Entity, &mut Fader)>,
+    mut commands: Commands,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut fader) in &mut fader_query {
+        let gain = approach(fader.gain, fader.target, fader.speed * delta);
+        if (gain - fader.gain).abs() > f32::EPSILON {
+            fader.gain = gain;
+        } else if fader.despawn && fader.gain <= f32::EPSILON {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Move `current` towards `target` by at most `step`
+fn approach(current: f32, target: f32, step: f32) -> f32 {
+    if current > target {
+        (current - step).max(target)
+    } else {
+        (current + step).min(target)
+    }
+}
+
+/// Sinks keep the volume they started with, so the bus gain has to be pushed to them by hand
+/// whenever it changes. New sounds are picked up as soon as their sink is created
+fn apply_bus_volume<S: Component + AudioSinkPlayback>(
+    settings: Res<Settings>,
+    ducking: Res<MusicDucking>,
+    sink_query: Query<(Ref<S>, &AudioBus, &SourceVolume, Option<Ref<Fader>>)>,
+) {
+    let changed = settings.is_changed() || ducking.is_changed();
+    for (sink, bus, volume, fader) in &sink_query {
+        let faded = fader.as_ref().is_some_and(DetectChanges::is_changed);
+        if changed || faded || sink.is_added() {
+            let fade = fader.map_or(1., |fader| fader.gain());
+            sink.set_volume(
+                bus.volume(volume.0, &settings, &ducking) * fade * settings.global_volume(),
+            );
+        }
+    }
+}
@@ -3,35 +3,100 @@ use bevy::{
     prelude::*,
 };
 
-use crate::game::assets::{HandleMap, SfxKey};
+use super::bus::{AudioBus, MusicDucking, SourceVolume};
+use crate::game::{
+    assets::{HandleMap, SfxKey},
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_listener);
     app.observe(play_sfx);
 }
 
+/// Distance between the listener's ears, in the same units sounds are placed around it
+static EAR_GAP: f32 = 1.;
+/// How far from the camera a sound can be, in pixels on screen at the current zoom, before it's
+/// at half volume
+static HEARING_RANGE: f32 = 1_000.;
+/// The quietest a positioned sound gets, however far away it is
+static MIN_ATTENUATION: f32 = 0.05;
+
+/// Positioned sounds are panned by placing them around this listener, which stays put at the
+/// origin. Where they go depends on where the sound is relative to the camera, so the listener
+/// doesn't have to follow the camera around
+fn spawn_listener(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Sfx Listener"),
+        SpatialListener::new(EAR_GAP),
+        TransformBundle::default(),
+    ));
+}
+
 fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    settings: Res<Settings>,
+    ducking: Res<MusicDucking>,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<Camera>>,
 ) {
-    let (sfx_key, volume, speed) = match trigger.event() {
-        PlaySfx::Key(key) => (*key, 1.0, 1.0),
-        PlaySfx::KeyVolSpeed(key, volume, speed) => (*key, *volume, *speed),
+    let (sfx_key, volume, speed, position) = match trigger.event() {
+        PlaySfx::Key(key) => (*key, 1.0, 1.0, None),
+        PlaySfx::KeyVolSpeedAt(key, volume, speed, position) => {
+            (*key, *volume, *speed, Some(*position))
+        }
     };
-    commands.spawn(AudioSourceBundle {
-        source: sfx_handles[&sfx_key].clone_weak(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            volume: Volume::new(volume),
-            speed,
-            ..default()
-        },
+    // Anything missing from the audio manifest was already reported by the loading screen
+    let Some(source) = sfx_handles.get(&sfx_key) else {
+        return;
+    };
+    let placement = position.and_then(|position| {
+        let (camera, projection) = camera_query.get_single().ok()?;
+        Some(place_sound(
+            position - camera.translation().xy(),
+            projection.scale,
+        ))
     });
+    let (volume, transform) = placement.map_or((volume, None), |(attenuation, pan)| {
+        (volume * attenuation, Some(pan))
+    });
+
+    let mut sound = commands.spawn((
+        AudioSourceBundle {
+            source: source.clone_weak(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(AudioBus::Sfx.volume(volume, &settings, &ducking)),
+                speed,
+                spatial: transform.is_some(),
+                ..default()
+            },
+        },
+        AudioBus::Sfx,
+        SourceVolume(volume),
+    ));
+    if let Some(transform) = transform {
+        sound.insert(TransformBundle::from_transform(transform));
+    }
+}
+
+/// Returns how much a sound is turned down for being `offset` away from the camera, and where to
+/// put it around the listener so it's panned towards the side of the screen it's on.
+/// Zooming out brings everything closer, since more of the system fits on screen
+fn place_sound(offset: Vec2, zoom: f32) -> (f32, Transform) {
+    let distance = offset / (zoom * HEARING_RANGE);
+    let attenuation = (1. / (1. + distance.length_squared())).max(MIN_ATTENUATION);
+    // Sounds a long way off are panned all the way over, ones on screen only slightly
+    let pan = distance.clamp_length_max(1.) * EAR_GAP;
+    (attenuation, Transform::from_translation(pan.extend(0.)))
 }
 
 /// Trigger this event to play a single sound effect.
 #[derive(Event)]
 pub enum PlaySfx {
     Key(SfxKey),
-    KeyVolSpeed(SfxKey, f32, f32),
+    /// Play the sound as if it came from a position in the world, so it's quieter the further it
+    /// is from the camera and panned towards the side it's on
+    KeyVolSpeedAt(SfxKey, f32, f32, Vec2),
 }
@@ -1,48 +1,190 @@
 use bevy::{
-    audio::{PlaybackMode, Volume},
+    audio::{AudioSinkPlayback, PlaybackMode, Volume},
     prelude::*,
 };
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use super::bus::{AudioBus, Fader, SourceVolume};
+use crate::{
+    game::{
+        assets::{HandleMap, SoundtrackKey},
+        flare::SpawnFlare,
+        sun::Sun,
+    },
+    screen::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
+    app.init_resource::<MusicIntensity>();
     app.observe(play_soundtrack);
+    app.observe(record_flare_activity);
+    app.add_systems(OnEnter(Screen::Playing), reset_music_intensity);
+    app.add_systems(
+        Update,
+        (update_music_intensity, update_soundtrack_tempo)
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Volume of the soundtrack with the music volume all the way up
+static SOUNDTRACK_VOLUME: f32 = 1.5;
+/// Seconds to crossfade between soundtracks
+static CROSSFADE_TIME: f32 = 2.;
+/// Speed the gameplay track plays at once the music turns tense
+static TENSE_SPEED: f32 = 1.25;
+/// The music turns tense once the intensity gets above the first, and calms back down below the
+/// second, so it doesn't flip back and forth around a single threshold
+static TENSE_INTENSITY: f32 = 0.6;
+static CALM_INTENSITY: f32 = 0.4;
+/// How much of the intensity comes from the solar cycle, with the rest from recent flares
+static CYCLE_WEIGHT: f32 = 0.6;
+/// Recent flare power that counts as the most intense flare activity
+static MAX_FLARE_ACTIVITY: f32 = 100.;
+/// Seconds for recent flare activity to halve
+static FLARE_ACTIVITY_HALF_LIFE: f32 = 30.;
+
+/// How tense the music should be, from 0 for calm to 1 for the height of the solar maximum
+#[derive(Resource, Debug, Default)]
+struct MusicIntensity {
+    level: f32,
+    flare_activity: f32,
+}
+
+/// The gameplay music's tense layer is the same track played faster and higher. Rather than mix
+/// two copies that drift apart, the one track speeds up into it and back down, carrying on from
+/// wherever it had got to
+#[derive(Component, Debug)]
+struct AdaptiveTempo {
+    tense: bool,
+    speed: f32,
+}
+
+impl AdaptiveTempo {
+    fn new(intensity: f32) -> Self {
+        let tense = intensity >= TENSE_INTENSITY;
+        Self {
+            tense,
+            speed: if tense { TENSE_SPEED } else { 1. },
+        }
+    }
 }
 
 fn play_soundtrack(
     trigger: Trigger<PlaySoundtrack>,
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-    soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    mut soundtrack_query: Query<&mut Fader, With<IsSoundtrack>>,
+    intensity: Res<MusicIntensity>,
 ) {
-    for entity in &soundtrack_query {
-        commands.entity(entity).despawn_recursive();
+    // Fade out whatever's playing rather than cutting it off, the new track fades in over it
+    for mut fader in &mut soundtrack_query {
+        fader.fade_out(CROSSFADE_TIME);
     }
 
-    let soundtrack_key = match trigger.event() {
-        PlaySoundtrack::Key(key) => *key,
-        PlaySoundtrack::Disable => return,
-    };
-    commands.spawn((
+    // Anything missing from the audio manifest was already reported by the loading screen
+    match trigger.event() {
+        PlaySoundtrack::Key(key) => {
+            if let Some(source) = soundtrack_handles.get(key) {
+                commands.spawn(soundtrack_bundle(source, 1.));
+            }
+        }
+        PlaySoundtrack::Adaptive => {
+            let Some(source) = soundtrack_handles.get(&SoundtrackKey::Gameplay) else {
+                return;
+            };
+            let tempo = AdaptiveTempo::new(intensity.level);
+            commands.spawn((soundtrack_bundle(source, tempo.speed), tempo));
+        }
+        PlaySoundtrack::Disable => {}
+    }
+}
+
+/// A looping track that fades in from silence
+fn soundtrack_bundle(source: &Handle<AudioSource>, speed: f32) -> impl Bundle {
+    (
         AudioSourceBundle {
-            source: soundtrack_handles[&soundtrack_key].clone_weak(),
+            source: source.clone_weak(),
             settings: PlaybackSettings {
                 mode: PlaybackMode::Loop,
-                volume: Volume::new(1.5),
+                // Faded up from silence once the sink exists
+                volume: Volume::new(0.),
+                speed,
                 ..default()
             },
         },
+        AudioBus::Music,
+        SourceVolume(SOUNDTRACK_VOLUME),
+        Fader::fade_in(1., CROSSFADE_TIME),
         IsSoundtrack,
-    ));
+    )
+}
+
+fn reset_music_intensity(mut intensity: ResMut<MusicIntensity>) {
+    *intensity = MusicIntensity::default();
+}
+
+fn record_flare_activity(trigger: Trigger<SpawnFlare>, mut intensity: ResMut<MusicIntensity>) {
+    intensity.flare_activity += trigger.event().power;
+}
+
+/// The music builds towards the solar maximum, and during bursts of flares
+fn update_music_intensity(
+    time: Res<Time>,
+    sun_query: Query<&Sun>,
+    mut intensity: ResMut<MusicIntensity>,
+) {
+    let Ok(sun) = sun_query.get_single() else {
+        return;
+    };
+    intensity.flare_activity *= 0.5_f32.powf(time.delta_seconds() / FLARE_ACTIVITY_HALF_LIFE);
+    let flares = (intensity.flare_activity / MAX_FLARE_ACTIVITY).min(1.);
+    let cycle = sun.raw_cycle_state().clamp(0., 1.);
+    intensity.level = CYCLE_WEIGHT.mul_add(cycle, (1. - CYCLE_WEIGHT) * flares);
+}
+
+/// Switch between the calm and tense tempo as the intensity crosses over, easing the speed across
+/// over a crossfade
+fn update_soundtrack_tempo(
+    time: Res<Time<Real>>,
+    intensity: Res<MusicIntensity>,
+    mut tempo_query: Query<(&mut AdaptiveTempo, Option<Ref<AudioSink>>)>,
+) {
+    for (mut tempo, sink) in &mut tempo_query {
+        let tense = if tempo.tense {
+            intensity.level > CALM_INTENSITY
+        } else {
+            intensity.level >= TENSE_INTENSITY
+        };
+        let target = if tense { TENSE_SPEED } else { 1. };
+        let step = (TENSE_SPEED - 1.) * time.delta_seconds() / CROSSFADE_TIME;
+        let speed = if tempo.speed > target {
+            (tempo.speed - step).max(target)
+        } else {
+            (tempo.speed + step).min(target)
+        };
+        // Only touch the tempo and sink when something changes
+        let changed = (speed - tempo.speed).abs() > f32::EPSILON;
+        if tense != tempo.tense || changed {
+            tempo.tense = tense;
+            tempo.speed = speed;
+        }
+        if let Some(sink) = sink {
+            if changed || sink.is_added() {
+                sink.set_speed(tempo.speed);
+            }
+        }
+    }
 }
 
 /// Trigger this event to play or disable the soundtrack.
-/// Playing a new soundtrack will overwrite the previous one.
+/// Playing a new soundtrack crossfades from the previous one.
 /// Soundtracks will loop.
 #[derive(Event)]
 pub enum PlaySoundtrack {
     Key(SoundtrackKey),
+    /// Play the gameplay music, which gets tenser with the solar cycle and flare activity
+    Adaptive,
     Disable,
 }
 
@@ -1,12 +1,25 @@
-use std::ops::Mul;
+//! The camera flies smoothly to whatever is selected and then follows it, until the player pans
+//! away by hand. Views can be saved to the number keys and flown back to later.
 
-use bevy::prelude::*;
+use bevy::{
+    input::{common_conditions::input_just_pressed, mouse::MouseMotion},
+    prelude::*,
+};
 use bevy_mod_picking::prelude::PickSelection;
+use bevy_pancam::{PanCam, PanCamSystemSet};
+use serde::{Deserialize, Serialize};
 
-use crate::screen::Screen;
+use super::{
+    navigation::NUMBER_KEYS,
+    planets::{DistanceView, Orbit, Planet},
+    settings::Settings,
+    spawn::planets::LAST_PLANET_DISTANCE,
+};
+use crate::screen::{Gameplay, Screen};
 
+/// Trigger this event to fly back to the selected body, such as when it's clicked again
 #[derive(Event, Debug)]
-pub struct ClearFinishZoomEvent;
+pub struct RefocusCamera;
 
 #[derive(Component, Debug)]
 pub struct ScaleWithZoom {
@@ -19,45 +32,139 @@ impl Default for ScaleWithZoom {
     }
 }
 
-#[derive(Debug, Component)]
-pub struct FinishZoom {
-    finished: bool,
-    target: f32,
+impl ScaleWithZoom {
+    /// How much bigger to draw things at the provided zoom, so they can still be seen zoomed out
+    fn factor(&self, scale: f32) -> f32 {
+        if scale > 15. {
+            (scale / 50. * self.ratio).clamp(1., MAX_ZOOM_FACTOR)
+        } else {
+            1.
+        }
+    }
 }
 
-impl Default for FinishZoom {
+/// The zoom the camera flies to when a body is selected
+#[derive(Debug, Component)]
+pub struct FocusZoom(pub f32);
+
+impl Default for FocusZoom {
     fn default() -> Self {
-        Self {
-            finished: false,
-            target: 15.,
-        }
+        Self(15.)
     }
 }
 
-impl FinishZoom {
-    pub const fn new_with_target(target: f32) -> Self {
-        Self {
-            finished: false,
-            target,
-        }
-    }
+/// Trigger this event to fly the camera somewhere, taking the flight time from the settings
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FlyTo {
+    pub target: CameraTarget,
+    pub scale: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraTarget {
+    Point(Vec2),
+    /// Keep following the body once the camera gets there
+    Body(Entity),
+}
+
+/// A view saved to one of the number keys, at its true scale position so it still points at the
+/// same place in any distance view
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub x: f32,
+    pub y: f32,
+    pub scale: f32,
+}
+
+/// The views saved to each number key, kept with the settings between games
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmarks(pub [Option<CameraBookmark>; 9]);
+
+/// What the camera's doing when it isn't being moved by hand
+#[derive(Resource, Debug, Default)]
+struct CameraControl {
+    mode: CameraMode,
+    /// Fly back to the selected body even though the selection hasn't changed
+    refocus: bool,
+}
+
+#[derive(Debug, Default)]
+enum CameraMode {
+    #[default]
+    Free,
+    Flying(Flight),
+    Following(Entity),
+}
+
+#[derive(Debug)]
+struct Flight {
+    from: Vec2,
+    from_scale: f32,
+    target: CameraTarget,
+    scale: f32,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Things that scale with zoom are never drawn more than this much bigger
+static MAX_ZOOM_FACTOR: f32 = 5.;
+/// Room left around whatever's being zoomed to fit, as a fraction of its size
+static FIT_MARGIN: f32 = 1.15;
+/// Pixels the mouse has to be dragged before it counts as panning, so clicks don't cancel a flight
+static DRAG_THRESHOLD: f32 = 4.;
+
 pub(super) fn plugin(app: &mut App) {
-    app.observe(clear_finish_zoom_event);
+    app.init_resource::<CameraControl>();
+    app.observe(refocus_camera);
+    app.observe(fly_to);
+    app.add_systems(OnEnter(Screen::Playing), reset_camera);
+    app.add_systems(
+        Update,
+        (
+            scale_with_zoom,
+            (cancel_on_pan, focus_selection, update_camera)
+                .chain()
+                .after(PanCamSystemSet),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
     app.add_systems(
         Update,
-        (scale_with_zoom, camera_follow).run_if(in_state(Screen::Playing)),
+        (
+            zoom_to_fit_system.run_if(input_just_pressed(KeyCode::KeyZ)),
+            zoom_to_fit_selection.run_if(input_just_pressed(KeyCode::KeyF)),
+            use_bookmarks,
+        )
+            .run_if(in_state(Gameplay::Running)),
     );
 }
 
-fn clear_finish_zoom_event(
-    _trigger: Trigger<ClearFinishZoomEvent>,
-    mut finish_zoom_query: Query<&mut FinishZoom>,
+fn reset_camera(mut control: ResMut<CameraControl>) {
+    *control = CameraControl::default();
+}
+
+fn refocus_camera(_trigger: Trigger<RefocusCamera>, mut control: ResMut<CameraControl>) {
+    control.refocus = true;
+}
+
+fn fly_to(
+    trigger: Trigger<FlyTo>,
+    settings: Res<Settings>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+    mut control: ResMut<CameraControl>,
 ) {
-    for mut finish_zoom in &mut finish_zoom_query {
-        finish_zoom.finished = false;
-    }
+    let Ok((transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let FlyTo { target, scale } = *trigger.event();
+    control.mode = CameraMode::Flying(Flight {
+        from: transform.translation.xy(),
+        from_scale: projection.scale,
+        target,
+        scale,
+        elapsed: 0.,
+        duration: settings.camera_flight_time,
+    });
 }
 
 fn scale_with_zoom(
@@ -65,44 +172,241 @@ fn scale_with_zoom(
     mut scaled_objects: Query<(&mut Transform, &ScaleWithZoom), With<ScaleWithZoom>>,
 ) {
     if let Ok(projection) = camera_projection.get_single() {
-        if projection.scale > 15. {
-            for (mut transform, scale) in &mut scaled_objects {
-                transform.scale =
-                    Vec3::splat((projection.scale / 50.).mul(scale.ratio).clamp(1., 5.));
-            }
-        } else {
-            for (mut transform, _scale) in &mut scaled_objects {
-                transform.scale = Vec3::ONE;
-            }
+        for (mut transform, scale) in &mut scaled_objects {
+            transform.scale = Vec3::splat(scale.factor(projection.scale));
         }
     }
 }
 
-fn camera_follow(
-    mut query_selectables: Query<
-        (&GlobalTransform, &PickSelection, &mut FinishZoom),
-        With<PickSelection>,
-    >,
-    mut camera_query: Query<
-        (&mut Transform, &mut OrthographicProjection),
-        (With<Camera>, Without<PickSelection>),
-    >,
+/// Dragging the view or moving it with the keys lets go of whatever the camera was following
+fn cancel_on_pan(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut motion: EventReader<MouseMotion>,
+    mut dragged: Local<f32>,
+    pancam_query: Query<&PanCam>,
+    mut control: ResMut<CameraControl>,
 ) {
-    for (transform, selected, mut finish_zoom) in &mut query_selectables {
-        if selected.is_selected {
-            // Make the camera follow that object, zoom to the max unscaled size
-            if let Ok((mut camera_transform, mut projection)) = camera_query.get_single_mut() {
-                camera_transform.translation = camera_transform
-                    .translation
-                    .lerp(transform.translation().xy().extend(0.), 0.1);
-                if !finish_zoom.finished && projection.scale > finish_zoom.target + 1.
-                    || projection.scale < 1.
-                {
-                    projection.scale = projection.scale.lerp(finish_zoom.target, 0.2);
-                } else {
-                    finish_zoom.finished = true;
+    let moved: f32 = motion.read().map(|motion| motion.delta.length()).sum();
+    let Ok(pancam) = pancam_query.get_single() else {
+        return;
+    };
+    if !pancam.enabled {
+        return;
+    }
+
+    let grabbed = pancam
+        .grab_buttons
+        .iter()
+        .any(|button| mouse.pressed(*button) && !mouse.just_pressed(*button));
+    *dragged = if grabbed { *dragged + moved } else { 0. };
+    let move_keys = &pancam.move_keys;
+    let keyed = [
+        &move_keys.up,
+        &move_keys.down,
+        &move_keys.left,
+        &move_keys.right,
+    ]
+    .into_iter()
+    .any(|keys_for_direction| keys.any_pressed(keys_for_direction.iter().copied()));
+
+    if *dragged > DRAG_THRESHOLD || keyed {
+        control.mode = CameraMode::Free;
+    }
+}
+
+/// Fly to whichever body was just selected
+fn focus_selection(
+    mut control: ResMut<CameraControl>,
+    selection_query: Query<(Entity, Ref<PickSelection>, &FocusZoom)>,
+    mut commands: Commands,
+) {
+    let refocus = std::mem::take(&mut control.refocus);
+    for (entity, selection, zoom) in &selection_query {
+        if selection.is_selected && (refocus || selection.is_changed()) {
+            commands.trigger(FlyTo {
+                target: CameraTarget::Body(entity),
+                scale: zoom.0,
+            });
+        }
+    }
+}
+
+fn update_camera(
+    time: Res<Time<Real>>,
+    mut control: ResMut<CameraControl>,
+    body_query: Query<&GlobalTransform>,
+    selection_query: Query<&PickSelection>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let Ok((mut camera_transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+    let position = |target: CameraTarget| match target {
+        CameraTarget::Point(point) => Some(point),
+        CameraTarget::Body(body) => body_query
+            .get(body)
+            .ok()
+            .map(|transform| transform.translation().xy()),
+    };
+    let z = camera_transform.translation.z;
+
+    let next_mode = match &mut control.mode {
+        CameraMode::Free => None,
+        CameraMode::Flying(flight) => {
+            // Flights run on real time, so one started just before pausing still gets there
+            flight.elapsed += time.delta_seconds();
+            let progress = if flight.duration > 0. {
+                (flight.elapsed / flight.duration).min(1.)
+            } else {
+                1.
+            };
+            let eased = ease_in_out(progress);
+            match position(flight.target) {
+                Some(target) => {
+                    camera_transform.translation = flight.from.lerp(target, eased).extend(z);
+                    // Zoom evenly in or out however many times bigger the view gets
+                    projection.scale =
+                        flight.from_scale * (flight.scale / flight.from_scale).powf(eased);
+                    (progress >= 1.).then_some(match flight.target {
+                        CameraTarget::Body(body) => CameraMode::Following(body),
+                        CameraTarget::Point(_) => CameraMode::Free,
+                    })
+                }
+                None => Some(CameraMode::Free),
+            }
+        }
+        CameraMode::Following(body) => {
+            // Following stops once nothing's selected any more
+            let selected = selection_query
+                .iter()
+                .any(|selection| selection.is_selected);
+            match position(CameraTarget::Body(*body)).filter(|_| selected) {
+                Some(target) => {
+                    camera_transform.translation = target.extend(z);
+                    None
                 }
+                None => Some(CameraMode::Free),
             }
         }
+    };
+    if let Some(mode) = next_mode {
+        control.mode = mode;
+    }
+}
+
+/// Starts and finishes slowly, and moves quickest in the middle
+fn ease_in_out(progress: f32) -> f32 {
+    progress * progress * 2.0f32.mul_add(-progress, 3.)
+}
+
+/// The zoom that fits a circle with the provided radius in the view, allowing for it being drawn
+/// bigger when zoomed out if it scales with zoom
+fn fit_scale(radius: f32, viewport: Vec2, scaling: Option<&ScaleWithZoom>) -> f32 {
+    let fit = |radius: f32| 2. * radius * FIT_MARGIN / viewport.min_element();
+    let close = fit(radius);
+    match scaling {
+        // Fitting it at its biggest is too far out, but it can't get any bigger closer in than that
+        Some(scaling) if scaling.factor(close) > 1. => {
+            fit(radius * scaling.factor(fit(radius * MAX_ZOOM_FACTOR)))
+        }
+        _ => close,
+    }
+}
+
+fn viewport_size(camera_query: &Query<&Camera>) -> Option<Vec2> {
+    camera_query
+        .get_single()
+        .ok()
+        .and_then(Camera::logical_viewport_size)
+}
+
+fn zoom_to_fit_system(
+    settings: Res<Settings>,
+    camera_query: Query<&Camera>,
+    mut commands: Commands,
+) {
+    if let Some(viewport) = viewport_size(&camera_query) {
+        commands.trigger(fit_system(viewport, settings.distance_view));
+    }
+}
+
+fn fit_system(viewport: Vec2, view: DistanceView) -> FlyTo {
+    FlyTo {
+        target: CameraTarget::Point(Vec2::ZERO),
+        scale: fit_scale(view.view_distance(*LAST_PLANET_DISTANCE), viewport, None),
+    }
+}
+
+/// Fit the selected planet and all its moons in the view, or the planet a selected moon goes
+/// around. With no planet selected the whole system is fitted instead
+fn zoom_to_fit_selection(
+    settings: Res<Settings>,
+    camera_query: Query<&Camera>,
+    selection_query: Query<(Entity, &PickSelection), With<Planet>>,
+    planet_query: Query<(&Planet, Option<&Parent>, Option<&ScaleWithZoom>)>,
+    moon_query: Query<(&Planet, &Orbit, &Parent)>,
+    mut commands: Commands,
+) {
+    let Some(viewport) = viewport_size(&camera_query) else {
+        return;
+    };
+    let planet = selection_query
+        .iter()
+        .find(|(_, selection)| selection.is_selected)
+        .and_then(|(entity, _)| {
+            let (planet, parent, _) = planet_query.get(entity).ok()?;
+            Some(if planet.is_moon {
+                parent?.get()
+            } else {
+                entity
+            })
+        });
+    let Some((planet, (body, _, scaling))) =
+        planet.and_then(|planet| Some((planet, planet_query.get(planet).ok()?)))
+    else {
+        commands.trigger(fit_system(viewport, settings.distance_view));
+        return;
+    };
+
+    let radius = moon_query
+        .iter()
+        .filter(|(moon, _, parent)| moon.is_moon && parent.get() == planet)
+        .map(|(moon, orbit, _)| orbit.radius() + moon.size)
+        .fold(body.size, f32::max);
+    commands.trigger(FlyTo {
+        target: CameraTarget::Body(planet),
+        scale: fit_scale(radius, viewport, scaling),
+    });
+}
+
+/// Ctrl and a number saves the current view to it, and Shift and the number flies back to it
+fn use_bookmarks(
+    input: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+    mut commands: Commands,
+) {
+    let Some(index) = NUMBER_KEYS.iter().position(|key| input.just_pressed(*key)) else {
+        return;
+    };
+    let view = settings.distance_view;
+    if input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        if let Ok((transform, projection)) = camera_query.get_single() {
+            let position = view.true_position(transform.translation.xy());
+            settings.camera_bookmarks.0[index] = Some(CameraBookmark {
+                x: position.x,
+                y: position.y,
+                scale: projection.scale,
+            });
+            settings.save();
+        }
+    } else if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        if let Some(bookmark) = settings.camera_bookmarks.0[index] {
+            commands.trigger(FlyTo {
+                target: CameraTarget::Point(view.view_position(Vec2::new(bookmark.x, bookmark.y))),
+                scale: bookmark.scale,
+            });
+        }
     }
 }
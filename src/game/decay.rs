@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 
-use crate::screen::Screen;
+use crate::screen::{Gameplay, Screen};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PreUpdate, clear_decay.run_if(in_state(Screen::Playing)));
-    app.add_systems(Update, update_decay.run_if(in_state(Screen::Playing)));
+    app.add_systems(Update, update_decay.run_if(in_state(Gameplay::Running)));
 }
 
 #[derive(Component, Debug)]
@@ -20,11 +20,34 @@ impl Decay {
             duration_secs: seconds,
         }
     }
+
+    /// Start the decay over again with a new duration
+    pub const fn restart(&mut self, seconds: f32) {
+        self.passed = 0.;
+        self.duration_secs = seconds;
+    }
+
+    /// Returns true once the full duration has passed
+    pub fn finished(&self) -> bool {
+        self.passed >= self.duration_secs
+    }
+
+    /// Returns how far through the duration it is, from 0.0 up to 1.0
+    pub fn fraction(&self) -> f32 {
+        (self.passed / self.duration_secs).clamp(0., 1.)
+    }
 }
 
-fn clear_decay(mut commands: Commands, query: Query<(Entity, &Decay), With<Decay>>) {
+/// Marks an entity that is reused once its `Decay` finishes, so it should not be despawned
+#[derive(Component, Debug)]
+pub struct Recycled;
+
+fn clear_decay(
+    mut commands: Commands,
+    query: Query<(Entity, &Decay), (With<Decay>, Without<Recycled>)>,
+) {
     for (entity, decay) in &query {
-        if decay.passed >= decay.duration_secs {
+        if decay.finished() {
             if let Some(entity_commands) = commands.get_entity(entity) {
                 entity_commands.despawn_recursive();
             }
@@ -32,7 +55,7 @@ fn clear_decay(mut commands: Commands, query: Query<(Entity, &Decay), With<Decay
     }
 }
 
-fn update_decay(time: Res<Time>, mut query: Query<&mut Decay, With<Decay>>) {
+pub(super) fn update_decay(time: Res<Time>, mut query: Query<&mut Decay, With<Decay>>) {
     for mut decay in &mut query {
         decay.passed += time.delta_seconds();
     }
@@ -1,20 +1,34 @@
 use std::{
     f32::consts::PI,
     ops::{Div, Mul, Sub},
+    sync::LazyLock,
 };
 
 use bevy::{
     prelude::*,
     sprite::{Material2d, MaterialMesh2dBundle},
+    utils::HashMap,
 };
 use rand::{thread_rng, Rng};
 
 use crate::{
-    game::{assets::SfxKey, audio::sfx::PlaySfx, camera::ScaleWithZoom},
-    screen::Screen,
+    game::{
+        assets::SfxKey,
+        audio::{bus::DuckMusic, sfx::PlaySfx},
+        camera::ScaleWithZoom,
+    },
+    screen::{Gameplay, Screen},
 };
 
-use super::{decay::Decay, planets::Planet, spawn::planets::LAST_PLANET_DISTANCE};
+use super::{
+    decay::{Decay, Recycled},
+    logistics::{Ship, SHIP_HIT_RADIUS},
+    notifications::{NotificationCategory, Notify},
+    planets::{DistanceView, Planet},
+    settings::Settings,
+    spawn::planets::{JUPITER_DISTANCE, LAST_PLANET_DISTANCE},
+    stations::OrbitalStation,
+};
 
 #[derive(Event, Debug)]
 pub struct SpawnFlare {
@@ -26,7 +40,10 @@ pub struct SpawnFlare {
 pub struct Velocity(Vec2);
 
 #[derive(Component, Debug)]
-pub struct Flare(f32);
+pub struct Flare {
+    power: f32,
+    active: bool,
+}
 
 #[derive(Bundle)]
 pub struct FlareBundle<M: Material2d> {
@@ -34,15 +51,72 @@ pub struct FlareBundle<M: Material2d> {
     mat_mesh: MaterialMesh2dBundle<M>,
     velocity: Velocity,
     decay: Decay,
+    recycled: Recycled,
+}
+
+/// How far out from the sun each flare still on its way has got, for showing where they're heading
+#[derive(Resource, Debug, Default)]
+pub struct FlareFronts(pub Vec<FlareFront>);
+
+#[derive(Debug, Clone, Copy)]
+pub struct FlareFront {
+    /// Direction the middle of the flare is heading in, in radians
+    pub angle: f32,
+    pub distance: f32,
 }
 
 #[derive(Resource)]
 struct FlareResources(Handle<Mesh>, Handle<ColorMaterial>);
 
+/// Flare particles that have finished and are waiting to be reused by the next flare
+#[derive(Resource, Debug, Default)]
+struct FlarePool(Vec<Entity>);
+
+impl FlarePool {
+    /// Hide the particle and hand it back to the pool
+    fn release(&mut self, entity: Entity, flare: &mut Flare, visibility: &mut Visibility) {
+        flare.active = false;
+        *visibility = Visibility::Hidden;
+        self.0.push(entity);
+    }
+}
+
+/// Speed of a flare's average particle
+static FLARE_SPEED: f32 = 2_000.;
+/// Seconds an average particle lasts before it fades away, long enough to get a little past
+/// Jupiter
+static FLARE_LIFETIME: LazyLock<f32> = LazyLock::new(|| *JUPITER_DISTANCE * 1.15 / FLARE_SPEED);
+/// Particles in each flare at the highest flare density setting
+static NUMBER_OF_FLARES: usize = 5_000;
+/// Flares at least this powerful duck the music under their thunder
+static DUCKING_POWER: f32 = 10.;
+/// Seconds before the same planet can be reported as hit by a flare again
+static FLARE_NOTICE_COOLDOWN: f32 = 20.;
+
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FlarePool>();
+    app.init_resource::<FlareFronts>();
     app.add_systems(Startup, build_flare_mesh);
+    app.add_systems(OnEnter(Screen::Playing), fill_flare_pool);
+    app.add_systems(OnExit(Screen::Playing), clear_flare_pool);
+    app.add_systems(
+        Update,
+        move_flares_to_view
+            .run_if(in_state(Screen::Playing).and_then(resource_changed::<Settings>)),
+    );
     app.observe(spawn_flare);
-    app.add_systems(Update, update_flares.run_if(in_state(Screen::Playing)));
+    app.add_systems(
+        Update,
+        (
+            apply_flare_gravity,
+            resolve_flare_collisions,
+            integrate_flares,
+            announce_flare_hits,
+            advance_flare_fronts,
+        )
+            .chain()
+            .run_if(in_state(Gameplay::Running)),
+    );
 }
 
 fn build_flare_mesh(
@@ -57,20 +131,96 @@ fn build_flare_mesh(
     commands.insert_resource(FlareResources(mesh, color));
 }
 
+fn flare_particle(
+    resources: &FlareResources,
+    flare: Flare,
+    velocity: Vec2,
+    lifetime: f32,
+) -> impl Bundle {
+    let visibility = if flare.active {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    (
+        FlareBundle {
+            flare,
+            mat_mesh: MaterialMesh2dBundle {
+                mesh: bevy::sprite::Mesh2dHandle(resources.0.clone()),
+                material: resources.1.clone(),
+                transform: Transform::from_xyz(0., 0., 2.),
+                visibility,
+                ..Default::default()
+            },
+            velocity: Velocity(velocity),
+            decay: Decay::new(lifetime),
+            recycled: Recycled,
+        },
+        StateScoped(Screen::Playing),
+        ScaleWithZoom { ratio: 0.1 },
+    )
+}
+
+/// Spawn enough hidden particles up front to cover a whole flare, so the first flare of a session
+/// doesn't have to spawn them all at once
+fn fill_flare_pool(
+    mut commands: Commands,
+    resources: Res<FlareResources>,
+    settings: Res<Settings>,
+    mut pool: ResMut<FlarePool>,
+) {
+    pool.0.clear();
+    for _ in 0..particles_per_flare(&settings) {
+        let particle = flare_particle(
+            &resources,
+            Flare {
+                power: 0.,
+                active: false,
+            },
+            Vec2::ZERO,
+            0.,
+        );
+        pool.0.push(commands.spawn(particle).id());
+    }
+}
+
+/// The pooled particles are state scoped, so they're already gone once we leave the game
+fn clear_flare_pool(mut pool: ResMut<FlarePool>, mut fronts: ResMut<FlareFronts>) {
+    pool.0.clear();
+    fronts.0.clear();
+}
+
+/// Lower flare densities split a flare into fewer particles
+fn particles_per_flare(settings: &Settings) -> usize {
+    NUMBER_OF_FLARES / usize::from(settings.flare_density.divisor())
+}
+
 fn spawn_flare(
     trigger: Trigger<SpawnFlare>,
     resources: Res<FlareResources>,
+    settings: Res<Settings>,
+    mut pool: ResMut<FlarePool>,
+    mut fronts: ResMut<FlareFronts>,
+    mut particle_query: Query<(
+        &mut Flare,
+        &mut Velocity,
+        &mut Decay,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    camera_query: Query<&GlobalTransform, With<Camera>>,
     mut commands: Commands,
 ) {
-    static NUMBER_OF_FLARES: usize = 5_000;
-    static FLARE_SPEED: f32 = 2_000.;
     static NORMAL_WIDTH: f32 = 0.5;
 
     // Pick an angle
     let primary_angle = f32::from(thread_rng().gen_range(0..359_u16));
     let width = trigger.event().size * NORMAL_WIDTH;
+    let power = trigger.event().power;
+    // Fewer particles each carry more power, so the flare hits just as hard at any density
+    let particle_power = power * f32::from(settings.flare_density.divisor());
 
-    for _ in 0..NUMBER_OF_FLARES {
+    for _ in 0..particles_per_flare(&settings) {
         let angle = width
             .mul_add(
                 f32::from(thread_rng().gen_range(1..100_u8)).div(100.),
@@ -79,26 +229,31 @@ fn spawn_flare(
             .to_radians();
 
         let speed_mult = f32::from(thread_rng().gen_range(800..1200_u16)).div(1000.);
-        let flare_speed = speed_mult * FLARE_SPEED;
-
-        commands.spawn((
-            FlareBundle {
-                flare: Flare(trigger.event().power),
-                mat_mesh: MaterialMesh2dBundle {
-                    mesh: bevy::sprite::Mesh2dHandle(resources.0.clone()),
-                    material: resources.1.clone(),
-                    transform: Transform::from_xyz(0., 0., 2.),
-                    ..Default::default()
+        let (velocity, lifetime) = particle_motion(angle, speed_mult);
+
+        // Reuse a finished particle if there is one, only spawning when the pool runs dry
+        if let Some((mut flare, mut flare_velocity, mut decay, mut transform, mut visibility)) =
+            pool.0
+                .pop()
+                .and_then(|entity| particle_query.get_mut(entity).ok())
+        {
+            flare.power = particle_power;
+            flare.active = true;
+            flare_velocity.0 = velocity;
+            decay.restart(lifetime);
+            transform.translation = Vec3::new(0., 0., 2.);
+            *visibility = Visibility::Inherited;
+        } else {
+            commands.spawn(flare_particle(
+                &resources,
+                Flare {
+                    power: particle_power,
+                    active: true,
                 },
-                velocity: Velocity(Vec2::new(
-                    flare_speed * angle.cos(),
-                    flare_speed * angle.sin(),
-                )),
-                decay: Decay::new(90. / speed_mult),
-            },
-            StateScoped(Screen::Playing),
-            ScaleWithZoom { ratio: 0.1 },
-        ));
+                velocity,
+                lifetime,
+            ));
+        }
     }
 
     let mut speed_mod = f32::from(thread_rng().gen_range(0..100_u8)).div(100.);
@@ -106,24 +261,115 @@ fn spawn_flare(
         speed_mod *= -1.;
     }
     let speed = speed_mod.mul_add(0.5, 1.0);
-    commands.trigger(PlaySfx::KeyVolSpeed(
+    let camera = camera_query
+        .get_single()
+        .map_or(Vec2::ZERO, |camera| camera.translation().xy());
+    let middle = (primary_angle + width / 2.).to_radians();
+    fronts.0.push(FlareFront {
+        angle: middle,
+        distance: 0.,
+    });
+    let furthest = settings.distance_view.view_distance(*LAST_PLANET_DISTANCE);
+    let heard_from = nearest_on_flare_path(camera, middle, furthest);
+    commands.trigger(PlaySfx::KeyVolSpeedAt(
         SfxKey::Thunder,
-        0.05 * trigger.event().power,
+        0.05 * power,
         speed,
+        heard_from,
     ));
+    if power >= DUCKING_POWER {
+        commands.trigger(DuckMusic);
+    }
+}
+
+/// The velocity and lifetime of a particle heading out at `angle` radians, `speed_mult` times as
+/// fast as the average particle. Faster particles fade sooner, so every particle gets just as far
+fn particle_motion(angle: f32, speed_mult: f32) -> (Vec2, f32) {
+    (
+        Vec2::from_angle(angle) * speed_mult * FLARE_SPEED,
+        *FLARE_LIFETIME / speed_mult,
+    )
+}
+
+/// The point along a flare heading out from the sun at `angle` that passes closest to `position`,
+/// which is where its thunder is heard from. Flares are never heard from further out than `furthest`
+fn nearest_on_flare_path(position: Vec2, angle: f32, furthest: f32) -> Vec2 {
+    let direction = Vec2::from_angle(angle);
+    direction * position.dot(direction).clamp(0., furthest)
+}
+
+/// The pull a planet has on a flare particle. Planets only affect flares that pass close by, and
+/// flares that are already being captured by the planet are handled by the collision step instead
+fn planet_gravity(flare: Vec2, planet: Vec2, size: f32) -> Vec2 {
+    let distance = flare.distance(planet);
+    if distance >= size * 2. || distance < size * 1.1 {
+        return Vec2::ZERO;
+    }
+    let force = (size * 50.) / distance.sub(size).div(30.).powi(2);
+    planet.sub(flare).normalize().mul(force)
 }
 
-fn update_flares(
+/// Sum up the pull from every planet before anything moves
+fn apply_flare_gravity(
     time: Res<Time>,
-    mut commands: Commands,
-    mut flare_query: Query<(&Flare, &mut Velocity, Entity, &mut Transform), With<Flare>>,
-    mut planet_query: Query<(&mut Planet, &GlobalTransform), (With<Planet>, Without<Flare>)>,
+    mut flare_query: Query<(&Flare, &mut Velocity, &Transform)>,
+    planet_query: Query<(&Planet, &GlobalTransform), Without<Flare>>,
 ) {
-    for (flare, mut velocity, entity, mut transform) in &mut flare_query {
-        if transform.translation.length() > *LAST_PLANET_DISTANCE * 1.25 {
-            if let Some(entity_commands) = commands.get_entity(entity) {
-                entity_commands.despawn_recursive();
-            }
+    for (flare, mut velocity, transform) in &mut flare_query {
+        if !flare.active {
+            continue;
+        }
+        let acceleration: Vec2 = planet_query
+            .iter()
+            .map(|(planet, planet_transform)| {
+                planet_gravity(
+                    transform.translation.xy(),
+                    planet_transform.translation().xy(),
+                    planet.size,
+                )
+            })
+            .sum();
+        velocity.0 += acceleration.mul(time.delta_seconds());
+    }
+}
+
+/// Absorb flares that hit a planet, and bend the ones that get captured or deflected by a
+/// magnetic field
+fn resolve_flare_collisions(
+    mut pool: ResMut<FlarePool>,
+    mut flare_query: Query<(
+        &mut Flare,
+        &mut Velocity,
+        Entity,
+        &Transform,
+        &mut Visibility,
+    )>,
+    mut planet_query: Query<(&mut Planet, &GlobalTransform), Without<Flare>>,
+    mut station_query: Query<(&mut OrbitalStation, &GlobalTransform)>,
+    mut ship_query: Query<(&mut Ship, &Transform), Without<Flare>>,
+) {
+    for (mut flare, mut velocity, entity, transform, mut visibility) in &mut flare_query {
+        if !flare.active {
+            continue;
+        }
+        // Stations orbit close enough that the planet would otherwise catch flares headed for them
+        let position = transform.translation.xy();
+        let hit_station = station_query
+            .iter_mut()
+            .find(|(station, station_transform)| {
+                position.distance(station_transform.translation().xy()) < station.size()
+            });
+        if let Some((mut station, _)) = hit_station {
+            station.absorb(flare.power);
+            pool.release(entity, &mut flare, &mut visibility);
+            continue;
+        }
+        let hit_ship = ship_query.iter_mut().find(|(_, ship_transform)| {
+            position.distance(ship_transform.translation.xy()) < SHIP_HIT_RADIUS
+        });
+        if let Some((mut ship, _)) = hit_ship {
+            ship.absorb(flare.power);
+            pool.release(entity, &mut flare, &mut visibility);
             continue;
         }
         for (mut planet, planet_transform) in &mut planet_query {
@@ -131,41 +377,267 @@ fn update_flares(
                 .translation
                 .xy()
                 .distance(planet_transform.translation().xy());
-            // Handle collision with the planet
-            if distance < planet.size * 2. {
-                let direction = planet_transform
-                    .translation()
-                    .xy()
-                    .sub(transform.translation.xy())
-                    .normalize();
-                if distance < planet.size {
-                    planet.absorbed_power += flare.0;
-                    if let Some(entity_commands) = commands.get_entity(entity) {
-                        entity_commands.despawn_recursive();
-                    }
-                    continue;
-                } else if distance < planet.size * 1.1 {
-                    velocity.0 = velocity
-                        .0
-                        .lerp(direction.mul(velocity.0.length() * 1.1), 0.2);
-                    continue;
-                } else if planet.has_magnetic_field {
-                    let mut perp = direction.perp();
-                    // We need to makee sure that it doesnt just suddenly make the flare go
-                    // backwards
-                    if direction.angle_between(velocity.0) < 0. {
-                        perp = Vec2::from_angle(perp.to_angle() + PI);
-                    }
-                    perp = perp.mul(velocity.0.length() * 1.1);
-                    velocity.0 = velocity.0.lerp(perp, 0.15);
-                    // Planets with magnetic fields still absorb solar flare energy, just at a
-                    // reduced rate
-                    planet.absorbed_power += flare.0 / 20.;
+            if distance >= planet.size * 2. {
+                continue;
+            }
+            let direction = planet_transform
+                .translation()
+                .xy()
+                .sub(transform.translation.xy())
+                .normalize();
+            if distance < planet.size {
+                planet.absorbed_power += flare.power;
+                pool.release(entity, &mut flare, &mut visibility);
+                break;
+            } else if distance < planet.size * 1.1 {
+                velocity.0 = velocity
+                    .0
+                    .lerp(direction.mul(velocity.0.length() * 1.1), 0.2);
+            } else if planet.has_magnetic_field {
+                let mut perp = direction.perp();
+                // We need to makee sure that it doesnt just suddenly make the flare go
+                // backwards
+                if direction.angle_between(velocity.0) < 0. {
+                    perp = Vec2::from_angle(perp.to_angle() + PI);
                 }
-                let force = (planet.size * 50.) / distance.sub(planet.size).div(30.).powi(2);
-                velocity.0 += direction.mul(force).mul(time.delta_seconds());
+                perp = perp.mul(velocity.0.length() * 1.1);
+                velocity.0 = velocity.0.lerp(perp, 0.15);
+                // Planets with magnetic fields still absorb solar flare energy, just at a
+                // reduced rate
+                planet.absorbed_power += flare.power / 20.;
+            }
+        }
+    }
+}
+
+/// Tell the player when a planet takes a significant hit, at most once per cooldown per planet
+fn announce_flare_hits(
+    time: Res<Time>,
+    planet_query: Query<(Entity, &Planet, &Name)>,
+    mut last_notice: Local<HashMap<Entity, (f32, f32)>>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_seconds();
+    for (entity, planet, name) in &planet_query {
+        let (noticed_at, absorbed_seen) = last_notice
+            .entry(entity)
+            .or_insert((f32::NEG_INFINITY, planet.absorbed_power));
+        let absorbed = planet.absorbed_power - *absorbed_seen;
+        if now - *noticed_at < FLARE_NOTICE_COOLDOWN || absorbed <= 0. {
+            continue;
+        }
+        *noticed_at = now;
+        *absorbed_seen = planet.absorbed_power;
+        commands.trigger(
+            Notify::new(NotificationCategory::Flare, format!("Flare hit {name}")).about(entity),
+        );
+    }
+}
+
+/// Move every flare exactly once, and recycle the ones that have run out of time or left the
+/// solar system. Flares move as far as they're drawn to, so they reach each planet at the same time
+/// in any distance view
+fn integrate_flares(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut pool: ResMut<FlarePool>,
+    mut flare_query: Query<(
+        &mut Flare,
+        &Velocity,
+        &Decay,
+        Entity,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+) {
+    let view = settings.distance_view;
+    let furthest = view.view_distance(*LAST_PLANET_DISTANCE * 1.25);
+    for (mut flare, velocity, decay, entity, mut transform, mut visibility) in &mut flare_query {
+        if !flare.active {
+            continue;
+        }
+        let stretch = view.stretch(view.true_distance(transform.translation.xy().length()));
+        transform.translation += velocity.0.extend(0.).mul(time.delta_seconds() * stretch);
+        if decay.finished() || transform.translation.length() > furthest {
+            pool.release(entity, &mut flare, &mut visibility);
+        }
+    }
+}
+
+/// Move flares already on their way to where they'd be drawn in the new view when it changes
+fn move_flares_to_view(
+    settings: Res<Settings>,
+    mut drawn_view: Local<DistanceView>,
+    mut flare_query: Query<(&Flare, &mut Transform)>,
+) {
+    let view = settings.distance_view;
+    if *drawn_view == view {
+        return;
+    }
+    for (flare, mut transform) in &mut flare_query {
+        if flare.active {
+            let position = view.view_position(drawn_view.true_position(transform.translation.xy()));
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+    *drawn_view = view;
+}
+
+/// Move each flare's front out at the average particle speed, dropping it once most of its
+/// particles would have faded or left the solar system
+fn advance_flare_fronts(time: Res<Time>, mut fronts: ResMut<FlareFronts>) {
+    let furthest = (FLARE_SPEED * *FLARE_LIFETIME).min(*LAST_PLANET_DISTANCE * 1.25);
+    for front in &mut fronts.0 {
+        front.distance += FLARE_SPEED * time.delta_seconds();
+    }
+    fronts.0.retain(|front| front.distance <= furthest);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::game::{decay::update_decay, planets::Atmosphere};
+
+    fn flare_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        app.init_resource::<FlarePool>();
+        app.init_resource::<Settings>();
+        app.add_systems(
+            Update,
+            (
+                apply_flare_gravity,
+                resolve_flare_collisions,
+                integrate_flares,
+            )
+                .chain(),
+        );
+        app
+    }
+
+    fn spawn_test_flare(app: &mut App, velocity: Vec2) -> Entity {
+        app.world_mut()
+            .spawn((
+                Flare {
+                    power: 1.,
+                    active: true,
+                },
+                Velocity(velocity),
+                Decay::new(100.),
+                Transform::default(),
+                Visibility::default(),
+            ))
+            .id()
+    }
+
+    fn spawn_test_planet(app: &mut App, position: Vec2, size: f32) {
+        app.world_mut().spawn((
+            Planet {
+                is_moon: false,
+                has_magnetic_field: false,
+                atmosphere: Atmosphere::None,
+                size,
+                absorbed_power: 0.,
+            },
+            GlobalTransform::from_translation(position.extend(0.)),
+        ));
+    }
+
+    fn step(app: &mut App, seconds: f32) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    fn distance_travelled(planets: usize) -> f32 {
+        let mut app = flare_app();
+        let flare = spawn_test_flare(&mut app, Vec2::new(100., 0.));
+        for i in 0..planets {
+            #[allow(clippy::cast_precision_loss)]
+            spawn_test_planet(&mut app, Vec2::new(0., 10_000. * (i + 1) as f32), 10.);
+        }
+        step(&mut app, 0.5);
+        app.world()
+            .get::<Transform>(flare)
+            .expect("Flare should still exist")
+            .translation
+            .length()
+    }
+
+    #[test]
+    fn flare_speed_does_not_depend_on_planet_count() {
+        let expected = distance_travelled(0);
+        assert!((expected - 50.).abs() < 0.001);
+        for planets in [1, 2, 8] {
+            assert!((distance_travelled(planets) - expected).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn nearby_planet_pulls_flare() {
+        let mut app = flare_app();
+        let flare = spawn_test_flare(&mut app, Vec2::new(100., 0.));
+        spawn_test_planet(&mut app, Vec2::new(0., 150.), 100.);
+        step(&mut app, 0.1);
+        let velocity = app.world().get::<Velocity>(flare).expect("Flare exists").0;
+        assert!(velocity.y > 0.);
+    }
+
+    #[test]
+    fn flare_hitting_planet_is_absorbed_and_recycled() {
+        let mut app = flare_app();
+        let flare = spawn_test_flare(&mut app, Vec2::new(100., 0.));
+        spawn_test_planet(&mut app, Vec2::new(5., 0.), 10.);
+        step(&mut app, 0.1);
+        assert!(
+            !app.world()
+                .get::<Flare>(flare)
+                .expect("Flare exists")
+                .active
+        );
+        assert_eq!(app.world().resource::<FlarePool>().0, vec![flare]);
+        let absorbed = app
+            .world_mut()
+            .query::<&Planet>()
+            .single(app.world())
+            .absorbed_power;
+        assert!((absorbed - 1.).abs() < f32::EPSILON);
+    }
+
+    /// Returns the furthest a particle gets from the sun, in true distance, before it fades
+    fn flare_reach(view: DistanceView, speed_mult: f32) -> f32 {
+        let mut app = flare_app();
+        app.add_systems(Update, update_decay.before(integrate_flares));
+        app.world_mut().resource_mut::<Settings>().distance_view = view;
+        let (velocity, lifetime) = particle_motion(0., speed_mult);
+        let flare = spawn_test_flare(&mut app, velocity);
+        app.world_mut()
+            .get_mut::<Decay>(flare)
+            .expect("Flare exists")
+            .restart(lifetime);
+        let mut reach: f32 = 0.;
+        while app
+            .world()
+            .get::<Flare>(flare)
+            .expect("Flare exists")
+            .active
+        {
+            let position = app.world().get::<Transform>(flare).expect("Flare exists");
+            reach = reach.max(view.true_distance(position.translation.length()));
+            step(&mut app, 0.25);
+        }
+        reach
+    }
+
+    #[test]
+    fn flares_reach_jupiter_in_any_view() {
+        for view in [DistanceView::TrueScale, DistanceView::Logarithmic] {
+            for speed_mult in [0.8, 1.2] {
+                assert!(flare_reach(view, speed_mult) > *JUPITER_DISTANCE);
             }
-            transform.translation += velocity.0.extend(0.).mul(time.delta_seconds());
         }
     }
 }
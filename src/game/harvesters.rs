@@ -0,0 +1,131 @@
+//! Building harvesters on planets, and the stockpile and power every game starts with to pay for
+//! and run the first ones.
+
+use bevy::prelude::*;
+
+use crate::screen::Screen;
+
+use super::{
+    maintenance::{BuildCost, Wear},
+    population::Workforce,
+    power::{PowerPriority, PowerSupply},
+    resources::{
+        cost_calculator, BuiltHarvesters, EnabledStructure, HarvestedResources, PlanetResources,
+        PoweredStructure, ProducingStructure, RawResource, RawResourceType, StationType,
+    },
+    stations::OrbitalStation,
+    unlocks::TechUnlocks,
+    upgrades::UpgradeLevel,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Playing), reset_harvested_resources);
+    app.observe(buy_harvester);
+}
+
+/// How much more expensive each additional harvester of the same resource on a planet gets
+pub static HARVESTER_COST_SCALING: f32 = 1.15;
+/// How much of its resource a harvester produces while enabled
+pub static HARVESTER_OUTPUT: f32 = 0.01;
+/// How much power the solar array every game starts with produces at one AU
+static SOLAR_ARRAY_OUTPUT: f32 = 2.;
+
+/// Returns the (metals, silicate) cost of building the next harvester for the provided resource
+pub fn harvester_cost(
+    resource: &RawResource,
+    harvesters: &BuiltHarvesters,
+    techs: &TechUnlocks,
+) -> (f32, f32) {
+    let cost = resource.cost(techs);
+    let built = harvesters.0.get(&resource.name()).map_or(0, Vec::len);
+    (
+        cost_calculator(cost.0, built, HARVESTER_COST_SCALING),
+        cost_calculator(cost.1, built, HARVESTER_COST_SCALING),
+    )
+}
+
+/// The solar array that powers the first structures built on the home planet
+pub fn starting_solar_array(planet: Entity) -> impl Bundle {
+    (
+        Name::new("Solar Array"),
+        ProducingStructure {
+            planet: Some(planet),
+            res_type: RawResourceType::Power,
+            produced: SOLAR_ARRAY_OUTPUT,
+            sun_buff: 1.,
+        },
+        SpatialBundle::default(),
+        StateScoped(Screen::Playing),
+    )
+}
+
+/// Trigger this event to build a harvester for the provided resource on a planet
+#[derive(Event, Debug)]
+pub struct BuyHarvester {
+    pub planet: Entity,
+    pub resource: RawResourceType,
+}
+
+fn buy_harvester(
+    trigger: Trigger<BuyHarvester>,
+    tech: Res<TechUnlocks>,
+    mut resources: ResMut<HarvestedResources>,
+    mut planet_query: Query<(&PlanetResources, &mut BuiltHarvesters)>,
+    mut commands: Commands,
+) {
+    let BuyHarvester { planet, resource } = *trigger.event();
+    let Ok((planet_resources, mut harvesters)) = planet_query.get_mut(planet) else {
+        return;
+    };
+    let Some(raw_resource) = planet_resources.get(resource) else {
+        return;
+    };
+    if !raw_resource.is_unlocked(&tech) {
+        return;
+    }
+    let cost = harvester_cost(raw_resource, &harvesters, &tech);
+    if resources.metals < cost.0 || resources.silicate < cost.1 {
+        return;
+    }
+    resources.metals -= cost.0;
+    resources.silicate -= cost.1;
+
+    let station = raw_resource.station_type();
+    let mut harvester = commands.spawn((
+        Name::new(format!("{resource} Harvester")),
+        ProducingStructure {
+            planet: Some(planet),
+            res_type: resource,
+            produced: HARVESTER_OUTPUT,
+            sun_buff: 0.,
+        },
+        PoweredStructure(station.power_draw()),
+        EnabledStructure(false),
+        UpgradeLevel::default(),
+        PowerPriority::default(),
+        PowerSupply::default(),
+        Wear::new(station),
+        BuildCost::new(cost.0, cost.1),
+        SpatialBundle::default(),
+        StateScoped(Screen::Playing),
+    ));
+    if station.workforce() > 0. {
+        harvester.insert(Workforce(station.workforce()));
+    }
+    if station == StationType::Orbit {
+        harvester.insert(OrbitalStation::new(planet));
+    }
+    let harvester = harvester.set_parent(planet).id();
+    harvesters.0.entry(resource).or_default().push(harvester);
+}
+
+/// Start each game with enough to build the first few structures on Earth
+fn reset_harvested_resources(mut resources: ResMut<HarvestedResources>) {
+    *resources = HarvestedResources {
+        metals: 500.,
+        silicate: 500.,
+        hydrogen: 50.,
+        oxygen: 50.,
+        power: 0.,
+    };
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::PickSelection;
 
-use super::planets::Planet;
+use super::{planets::Planet, sun::Sun};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -18,7 +18,10 @@ pub struct LinkSelectionObject(pub Entity);
 
 fn handle_linked_selection_objects(
     mut ring_query: Query<(&LinkSelectionObject, &mut PickSelection), With<LinkSelectionObject>>,
-    mut planet_query: Query<&mut PickSelection, (With<Planet>, Without<LinkSelectionObject>)>,
+    mut planet_query: Query<
+        &mut PickSelection,
+        (Or<(With<Planet>, With<Sun>)>, Without<LinkSelectionObject>),
+    >,
 ) {
     for (link, mut selected) in &mut ring_query {
         if selected.is_selected {
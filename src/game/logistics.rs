@@ -0,0 +1,317 @@
+//! Optional logistics mode, where resources harvested away from the home world have to be shipped
+//! there along player defined routes before they can be spent.
+
+use std::ops::Mul;
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use crate::screen::{Gameplay, Screen};
+
+use super::{
+    camera::ScaleWithZoom,
+    notifications::{NotificationCategory, Notify},
+    planets::{DistanceView, Orbit},
+    resources::{HarvestedResources, RawResourceType},
+    settings::Settings,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LogisticsSettings>();
+    app.add_systems(Startup, build_ship_mesh);
+    app.observe(toggle_route);
+    app.add_systems(
+        Update,
+        (
+            toggle_logistics
+                .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::KeyL))),
+            (dispatch_ships, move_ships, wreck_ships)
+                .chain()
+                .run_if(in_state(Gameplay::Running)),
+        ),
+    );
+}
+
+/// Seconds between ships leaving along the same route
+static DISPATCH_INTERVAL: f32 = 10.;
+/// The most cargo a single ship can carry
+static SHIP_CAPACITY: f32 = 20.;
+/// Ships don't bother leaving with less cargo than this
+static MINIMUM_CARGO: f32 = 1.;
+/// Seconds every trip takes on top of the time spent crossing between orbits
+static BASE_TRAVEL_TIME: f32 = 20.;
+/// Distance between orbits a ship covers per second
+static SHIP_SPEED: f32 = 2_000.;
+/// Hydrogen burned per unit of distance travelled
+static FUEL_PER_DISTANCE: f32 = 0.000_1;
+/// How close a flare particle needs to get to hit a ship
+pub static SHIP_HIT_RADIUS: f32 = 300.;
+static SHIP_INTEGRITY: f32 = 100.;
+
+#[derive(Resource, Debug, Default)]
+pub struct LogisticsSettings {
+    pub enabled: bool,
+}
+
+/// The planet everything gets shipped to. Its stockpile is the global `HarvestedResources`
+#[derive(Component, Debug)]
+pub struct HomeWorld;
+
+/// Resources harvested on a planet that are waiting to be shipped
+#[derive(Component, Debug, Default, Deref, DerefMut)]
+pub struct LocalStockpile(pub HarvestedResources);
+
+#[derive(Component, Debug)]
+pub struct TransportRoute {
+    pub from: Entity,
+    pub to: Entity,
+    pub resource: RawResourceType,
+    since_departure: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct Ship {
+    to: Entity,
+    resource: RawResourceType,
+    cargo: f32,
+    /// Where the ship really leaves from and arrives at, whichever distance view it's drawn in
+    departure: Vec2,
+    arrival: Vec2,
+    elapsed: f32,
+    duration: f32,
+    integrity: f32,
+}
+
+impl Ship {
+    /// Returns where the ship is drawn along the transfer between the two orbits at the current
+    /// time
+    fn position(&self, view: DistanceView) -> Vec2 {
+        // Ease in and out so the ship speeds up leaving one orbit and slows down entering the next
+        let t = (self.elapsed / self.duration).clamp(0., 1.);
+        let t = t * t * 2.0f32.mul_add(-t, 3.);
+        let radius = self.departure.length().lerp(self.arrival.length(), t);
+        // Always travel prograde, the same direction the planets orbit in
+        let start = self.departure.to_angle();
+        let sweep = (self.arrival.to_angle() - start).rem_euclid(std::f32::consts::TAU);
+        view.view_position(Vec2::from_angle(sweep.mul_add(t, start)).mul(radius))
+    }
+
+    pub fn absorb(&mut self, power: f32) {
+        self.integrity -= power;
+    }
+}
+
+/// Trigger this event to start or stop shipping a resource between two planets
+#[derive(Event, Debug)]
+pub struct ToggleRoute {
+    pub from: Entity,
+    pub to: Entity,
+    pub resource: RawResourceType,
+}
+
+#[derive(Resource)]
+struct ShipResources(Handle<Mesh>, Handle<ColorMaterial>);
+
+fn build_ship_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mesh = meshes.add(Triangle2d::new(
+        Vec2::new(0., 60.),
+        Vec2::new(-40., -40.),
+        Vec2::new(40., -40.),
+    ));
+    let color = materials.add(Color::srgb(0.8, 0.9, 1.));
+    commands.insert_resource(ShipResources(mesh, color));
+}
+
+fn toggle_logistics(mut logistics: ResMut<LogisticsSettings>) {
+    logistics.enabled = !logistics.enabled;
+    info!("Logistics mode enabled: {}", logistics.enabled);
+}
+
+fn toggle_route(
+    trigger: Trigger<ToggleRoute>,
+    route_query: Query<(Entity, &TransportRoute)>,
+    mut commands: Commands,
+) {
+    let ToggleRoute { from, to, resource } = *trigger.event();
+    if let Some((entity, _)) = route_query
+        .iter()
+        .find(|(_, route)| route.from == from && route.to == to && route.resource == resource)
+    {
+        commands.entity(entity).despawn_recursive();
+    } else {
+        commands.spawn((
+            Name::new(format!("{resource} Route")),
+            TransportRoute {
+                from,
+                to,
+                resource,
+                since_departure: DISPATCH_INTERVAL,
+            },
+            StateScoped(Screen::Playing),
+        ));
+    }
+}
+
+/// Returns the real position a body will be at after the provided number of seconds, following
+/// its parents for moons
+fn predict_position(
+    entity: Entity,
+    seconds: f32,
+    orbit_query: &Query<(&Orbit, Option<&Parent>)>,
+) -> Vec2 {
+    orbit_query
+        .get(entity)
+        .map_or(Vec2::ZERO, |(orbit, parent)| {
+            orbit.predict(seconds)
+                + parent.map_or(Vec2::ZERO, |parent| {
+                    predict_position(parent.get(), seconds, orbit_query)
+                })
+        })
+}
+
+/// Returns the stockpile a planet ships from and delivers to
+fn depot<'a>(
+    planet: Entity,
+    home: bool,
+    global: &'a mut HarvestedResources,
+    stockpile_query: &'a mut Query<&mut LocalStockpile>,
+) -> Option<&'a mut HarvestedResources> {
+    if home {
+        Some(global)
+    } else {
+        stockpile_query
+            .get_mut(planet)
+            .ok()
+            .map(|stockpile| &mut stockpile.into_inner().0)
+    }
+}
+
+fn dispatch_ships(
+    time: Res<Time>,
+    logistics: Res<LogisticsSettings>,
+    settings: Res<Settings>,
+    ship_resources: Res<ShipResources>,
+    mut resources: ResMut<HarvestedResources>,
+    mut route_query: Query<&mut TransportRoute>,
+    orbit_query: Query<(&Orbit, Option<&Parent>)>,
+    planet_query: Query<(&GlobalTransform, Has<HomeWorld>)>,
+    mut stockpile_query: Query<&mut LocalStockpile>,
+    mut commands: Commands,
+) {
+    if !logistics.enabled {
+        return;
+    }
+    for mut route in &mut route_query {
+        route.since_departure += time.delta_seconds();
+        if route.since_departure < DISPATCH_INTERVAL {
+            continue;
+        }
+        let (Ok((from_transform, from_home)), Ok((to_transform, _))) =
+            (planet_query.get(route.from), planet_query.get(route.to))
+        else {
+            continue;
+        };
+        let view = settings.distance_view;
+        let departure = view.true_position(from_transform.translation().xy());
+
+        // Travel time depends on how far apart the two orbits really are, and the ship has to aim
+        // for where the destination will be once it gets there
+        let destination = view.true_position(to_transform.translation().xy());
+        let orbit_gap = (departure.length() - destination.length()).abs();
+        let duration = orbit_gap / SHIP_SPEED + BASE_TRAVEL_TIME;
+        let arrival = predict_position(route.to, duration, &orbit_query);
+        let fuel = departure.distance(arrival) * FUEL_PER_DISTANCE;
+        if resources.hydrogen < fuel {
+            continue;
+        }
+
+        let Some(stock) = depot(route.from, from_home, &mut resources, &mut stockpile_query)
+            .map(|depot| depot.get_mut(route.resource))
+        else {
+            continue;
+        };
+        if *stock < MINIMUM_CARGO {
+            continue;
+        }
+        let cargo = stock.min(SHIP_CAPACITY);
+        *stock -= cargo;
+        resources.hydrogen -= fuel;
+        route.since_departure = 0.;
+
+        commands.spawn((
+            Name::new(format!("{} Ship", route.resource)),
+            Ship {
+                to: route.to,
+                resource: route.resource,
+                cargo,
+                departure,
+                arrival,
+                elapsed: 0.,
+                duration,
+                integrity: SHIP_INTEGRITY,
+            },
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(ship_resources.0.clone()),
+                material: ship_resources.1.clone(),
+                transform: Transform::from_translation(view.view_position(departure).extend(3.)),
+                ..Default::default()
+            },
+            ScaleWithZoom { ratio: 0.2 },
+            StateScoped(Screen::Playing),
+        ));
+    }
+}
+
+fn move_ships(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut resources: ResMut<HarvestedResources>,
+    mut ship_query: Query<(Entity, &mut Ship, &mut Transform)>,
+    home_query: Query<(), With<HomeWorld>>,
+    mut stockpile_query: Query<&mut LocalStockpile>,
+    mut commands: Commands,
+) {
+    for (entity, mut ship, mut transform) in &mut ship_query {
+        ship.elapsed += time.delta_seconds();
+        let previous = transform.translation.xy();
+        let position = ship.position(settings.distance_view);
+        transform.translation = position.extend(transform.translation.z);
+        if position != previous {
+            transform.rotation = Quat::from_rotation_z(
+                (position - previous).to_angle() - std::f32::consts::FRAC_PI_2,
+            );
+        }
+
+        if ship.elapsed >= ship.duration {
+            let home = home_query.contains(ship.to);
+            if let Some(depot) = depot(ship.to, home, &mut resources, &mut stockpile_query) {
+                *depot.get_mut(ship.resource) += ship.cargo;
+            }
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Ships in transit have no planet to hide behind, so flares can destroy them along with their
+/// cargo
+fn wreck_ships(ship_query: Query<(Entity, &Ship)>, mut commands: Commands) {
+    for (entity, ship) in &ship_query {
+        if ship.integrity <= 0. {
+            commands.trigger(
+                Notify::new(
+                    NotificationCategory::Logistics,
+                    format!("A {} ship was destroyed by a solar flare", ship.resource),
+                )
+                .about(ship.to),
+            );
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Harvesters wear down over time and under flare exposure, and need regular maintenance to keep
+//! producing at full rate. Ones that are no longer worth keeping can be decommissioned.
+
+use bevy::prelude::*;
+
+use crate::screen::Gameplay;
+
+use super::{
+    planets::Planet,
+    resources::{
+        BuiltHarvesters, EnabledStructure, HarvestedResources, ProducingStructure, RawResourceType,
+        StationType,
+    },
+    stations::OrbitalStation,
+    upgrades::UpgradeLevel,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(decommission_harvester);
+    app.add_systems(
+        Update,
+        (wear_structures, flare_wear, maintain_structures)
+            .chain()
+            .run_if(in_state(Gameplay::Running)),
+    );
+}
+
+/// Condition lost per second while a structure is running
+static WEAR_RATE: f32 = 0.001;
+/// Condition lost per unit of flare energy that reaches a structure
+static FLARE_WEAR: f32 = 0.000_5;
+/// Seconds between maintenance visits
+static MAINTENANCE_INTERVAL: f32 = 30.;
+/// Metals each maintenance visit costs per upgrade tier
+static MAINTENANCE_COST: f32 = 25.;
+/// Condition restored by each maintenance visit
+static MAINTENANCE_REPAIR: f32 = 0.3;
+/// Fraction of the build cost refunded when decommissioning a structure in perfect condition
+static REFUND_RATE: f32 = 0.5;
+
+#[derive(Component, Debug)]
+pub struct Wear {
+    station: StationType,
+    /// 1.0 for a new structure, down to 0.0 for one that has completely broken down
+    condition: f32,
+    since_maintenance: f32,
+    /// Flare energy that had already reached the structure the last time it was checked. `None`
+    /// until the first check, so a new structure doesn't take the wear from every earlier flare
+    absorbed_seen: Option<f32>,
+}
+
+impl Wear {
+    pub const fn new(station: StationType) -> Self {
+        Self {
+            station,
+            condition: 1.,
+            since_maintenance: 0.,
+            absorbed_seen: None,
+        }
+    }
+
+    /// Returns the structure's condition, which its output is scaled by
+    pub const fn condition(&self) -> f32 {
+        self.condition
+    }
+}
+
+/// Everything paid for a structure, from building it through each upgrade since
+#[derive(Component, Debug, Clone)]
+pub struct BuildCost(pub Vec<(f32, RawResourceType)>);
+
+impl BuildCost {
+    /// The cost of building a harvester, before any upgrades
+    pub fn new(metals: f32, silicate: f32) -> Self {
+        Self(vec![
+            (metals, RawResourceType::Metals),
+            (silicate, RawResourceType::Silicate),
+        ])
+    }
+
+    /// Add the cost of an upgrade on top of what has been paid so far
+    pub fn add(&mut self, cost: &[(f32, RawResourceType)]) {
+        self.0.extend_from_slice(cost);
+    }
+}
+
+/// Trigger this event to tear down a harvester and get part of its build cost back
+#[derive(Event, Debug)]
+pub struct DecommissionHarvester {
+    pub harvester: Entity,
+}
+
+fn wear_structures(time: Res<Time>, mut structure_query: Query<(&mut Wear, &EnabledStructure)>) {
+    for (mut wear, enabled) in &mut structure_query {
+        if enabled.0 {
+            wear.condition = WEAR_RATE
+                .mul_add(-time.delta_seconds(), wear.condition)
+                .max(0.);
+        }
+    }
+}
+
+/// Flares wear down orbital stations they hit, and surface structures on planets without a
+/// magnetic field as far as the atmosphere lets them through
+fn flare_wear(
+    mut structure_query: Query<(&mut Wear, &Parent, Option<&OrbitalStation>)>,
+    planet_query: Query<&Planet>,
+) {
+    for (mut wear, parent, station) in &mut structure_query {
+        let exposed = if let Some(station) = station {
+            station.absorbed_power()
+        } else if let Ok(planet) = planet_query.get(parent.get()) {
+            planet.exposed_power(wear.station)
+        } else {
+            continue;
+        };
+        let absorbed = exposed - wear.absorbed_seen.unwrap_or(exposed);
+        wear.absorbed_seen = Some(exposed);
+        if absorbed > 0. {
+            wear.condition = FLARE_WEAR.mul_add(-absorbed, wear.condition).max(0.);
+        }
+    }
+}
+
+fn maintain_structures(
+    time: Res<Time>,
+    mut resources: ResMut<HarvestedResources>,
+    mut structure_query: Query<(&mut Wear, Option<&UpgradeLevel>)>,
+) {
+    for (mut wear, level) in &mut structure_query {
+        wear.since_maintenance += time.delta_seconds();
+        if wear.since_maintenance < MAINTENANCE_INTERVAL {
+            continue;
+        }
+        wear.since_maintenance = 0.;
+        // Structures that are skipped have to wait for the next visit
+        #[allow(clippy::cast_precision_loss)]
+        let cost = MAINTENANCE_COST * (level.map_or(0, |level| **level) + 1) as f32;
+        if resources.metals >= cost {
+            resources.metals -= cost;
+            wear.condition = (wear.condition + MAINTENANCE_REPAIR).min(1.);
+        }
+    }
+}
+
+fn decommission_harvester(
+    trigger: Trigger<DecommissionHarvester>,
+    mut resources: ResMut<HarvestedResources>,
+    harvester_query: Query<(&ProducingStructure, &BuildCost, Option<&Wear>)>,
+    mut planet_query: Query<&mut BuiltHarvesters>,
+    mut commands: Commands,
+) {
+    let harvester = trigger.event().harvester;
+    let Ok((producing, cost, wear)) = harvester_query.get(harvester) else {
+        return;
+    };
+    // Removing it from the list is what makes the next harvester on the planet cheaper again
+    if let Some(mut harvesters) = producing
+        .planet
+        .and_then(|planet| planet_query.get_mut(planet).ok())
+    {
+        if let Some(built) = harvesters.0.get_mut(&producing.res_type) {
+            built.retain(|entity| *entity != harvester);
+        }
+    }
+    let refund = REFUND_RATE * wear.map_or(1., Wear::condition);
+    for (amount, resource) in &cost.0 {
+        *resources.get_mut(*resource) += amount * refund;
+    }
+    commands.entity(harvester).despawn_recursive();
+}
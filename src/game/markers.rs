@@ -0,0 +1,175 @@
+//! Markers showing the harvesters built on each planet, dimmed while they are stalled.
+
+use bevy::{
+    color::Luminance,
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::HashMap,
+};
+
+use crate::screen::Screen;
+
+use super::{
+    planets::{Orbit, Planet},
+    resources::{EnabledStructure, PlanetResources, ProducingStructure, StationType},
+    stations::OrbitalStation,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, build_marker_resources);
+    app.add_systems(
+        Update,
+        (
+            place_harvester_markers,
+            turn_harvester_markers,
+            dim_stalled_harvesters,
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Size of surface and ocean markers as a multiple of their planet's size
+static MARKER_SIZE: f32 = 0.12;
+/// Degrees between each marker and the next one built on the same planet
+static MARKER_SPACING: f32 = 137.5;
+/// How much darker a marker gets while its harvester is stalled
+static STALLED_DIMMING: f32 = 0.4;
+
+/// Shows a harvester on its planet
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HarvesterMarker {
+    station: StationType,
+    /// Radians around the planet from the side facing away from the sun
+    angle: f32,
+}
+
+impl HarvesterMarker {
+    pub const fn new(station: StationType) -> Self {
+        Self { station, angle: 0. }
+    }
+}
+
+/// The mesh used for surface and ocean markers, and materials for every station type
+#[derive(Resource)]
+pub struct MarkerResources {
+    rig: Handle<Mesh>,
+    surface: (Handle<ColorMaterial>, Handle<ColorMaterial>),
+    ocean: (Handle<ColorMaterial>, Handle<ColorMaterial>),
+    orbit: (Handle<ColorMaterial>, Handle<ColorMaterial>),
+}
+
+impl MarkerResources {
+    /// Returns the material for a marker of the provided station type
+    pub fn material(&self, station: StationType, enabled: bool) -> Handle<ColorMaterial> {
+        let (active, stalled) = match station {
+            StationType::Surface => &self.surface,
+            StationType::Ocean => &self.ocean,
+            StationType::Orbit => &self.orbit,
+        };
+        if enabled {
+            active.clone()
+        } else {
+            stalled.clone()
+        }
+    }
+}
+
+fn build_marker_resources(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mut pair = |color: Color| {
+        (
+            materials.add(color),
+            materials.add(color.darker(STALLED_DIMMING)),
+        )
+    };
+    let surface = pair(Color::srgb(0.95, 0.65, 0.3));
+    let ocean = pair(Color::srgb(0.3, 0.65, 1.));
+    let orbit = pair(Color::srgb(0.75, 0.75, 0.8));
+    commands.insert_resource(MarkerResources {
+        rig: meshes.add(Rectangle::new(2., 1.)),
+        surface,
+        ocean,
+        orbit,
+    });
+}
+
+/// Put a marker on the rim of the planet for each new surface or ocean harvester, spread out
+/// from the markers that are already there
+fn place_harvester_markers(
+    marker_resources: Res<MarkerResources>,
+    harvester_query: Query<
+        (Entity, &ProducingStructure, &Parent),
+        (Added<ProducingStructure>, Without<OrbitalStation>),
+    >,
+    placed_query: Query<&Parent, (With<HarvesterMarker>, Without<OrbitalStation>)>,
+    planet_query: Query<(&Planet, &PlanetResources)>,
+    mut commands: Commands,
+) {
+    let mut placed: HashMap<Entity, usize> = HashMap::new();
+    for parent in &placed_query {
+        *placed.entry(parent.get()).or_default() += 1;
+    }
+    for (entity, producing, parent) in &harvester_query {
+        let Ok((planet, resources)) = planet_query.get(parent.get()) else {
+            continue;
+        };
+        // Structures that don't harvest anything from the planet, like solar arrays, aren't shown
+        let Some(resource) = resources.get(producing.res_type) else {
+            continue;
+        };
+        let placed = placed.entry(parent.get()).or_default();
+        #[allow(clippy::cast_precision_loss)]
+        let angle = (*placed as f32 * MARKER_SPACING).to_radians();
+        *placed += 1;
+
+        let station = resource.station_type();
+        commands.entity(entity).insert((
+            HarvesterMarker { station, angle },
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(marker_resources.rig.clone()),
+                material: marker_resources.material(station, false),
+                transform: Transform::from_xyz(0., 0., 1.5)
+                    .with_scale(Vec3::splat(planet.size * MARKER_SIZE)),
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+/// Keep markers on the rim of their planet, turning with it as it goes around its orbit
+fn turn_harvester_markers(
+    mut marker_query: Query<(&HarvesterMarker, &Parent, &mut Transform), Without<Orbit>>,
+    planet_query: Query<(&Planet, &Orbit)>,
+) {
+    for (marker, parent, mut transform) in &mut marker_query {
+        let Ok((planet, orbit)) = planet_query.get(parent.get()) else {
+            continue;
+        };
+        let angle = orbit.angle().to_radians() + marker.angle;
+        transform.translation = (Vec2::from_angle(angle) * planet.size).extend(1.5);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+fn dim_stalled_harvesters(
+    marker_resources: Res<MarkerResources>,
+    mut marker_query: Query<
+        (
+            &HarvesterMarker,
+            &EnabledStructure,
+            &mut Handle<ColorMaterial>,
+        ),
+        Changed<EnabledStructure>,
+    >,
+) {
+    for (marker, enabled, mut material) in &mut marker_query {
+        let wanted = marker_resources.material(marker.station, enabled.0);
+        if *material != wanted {
+            *material = wanted;
+        }
+    }
+}
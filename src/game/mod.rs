@@ -8,12 +8,25 @@ pub mod audio;
 pub mod camera;
 pub mod decay;
 pub mod flare;
+pub mod harvesters;
 pub mod highlight;
+pub mod logistics;
+pub mod maintenance;
+pub mod markers;
+pub mod navigation;
+pub mod notifications;
 pub mod planets;
+pub mod population;
+pub mod power;
 pub mod resources;
+pub mod save;
+pub mod settings;
 pub mod spawn;
+pub mod stations;
 pub mod sun;
+pub mod transfer;
 pub mod unlocks;
+pub mod upgrades;
 
 pub fn plugin(app: &mut App) {
     app.add_plugins((
@@ -30,4 +43,19 @@ pub fn plugin(app: &mut App) {
         resources::plugin,
         unlocks::plugin,
     ));
+    app.add_plugins((
+        population::plugin,
+        harvesters::plugin,
+        logistics::plugin,
+        transfer::plugin,
+        stations::plugin,
+        markers::plugin,
+        upgrades::plugin,
+        maintenance::plugin,
+        power::plugin,
+        notifications::plugin,
+        navigation::plugin,
+        settings::plugin,
+        save::plugin,
+    ));
 }
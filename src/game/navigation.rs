@@ -0,0 +1,197 @@
+//! Keyboard controls for picking planets, moons and the sun without having to click on them.
+//!
+//! Everything goes through `PickSelection`, the same as a click would, so the camera and planet UI
+//! follow along.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_mod_picking::prelude::PickSelection;
+
+use crate::screen::Gameplay;
+
+use super::{
+    camera::RefocusCamera,
+    planets::{Orbit, Planet},
+    sun::Sun,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(select_body);
+    app.add_systems(
+        Update,
+        (
+            deselect_or_pause.run_if(input_just_pressed(KeyCode::Escape)),
+            centre_on_sun.run_if(input_just_pressed(KeyCode::KeyC)),
+            navigate_bodies,
+        )
+            .run_if(in_state(Gameplay::Running)),
+    );
+}
+
+/// Number keys select planets in order out from the sun
+pub static NUMBER_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Trigger this event to select a planet, moon or the sun as if it had been clicked on
+#[derive(Event, Debug)]
+pub struct SelectBody(pub Entity);
+
+/// Planets and moons, with the orbit they're sorted by and the planet a moon goes around
+type BodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Planet,
+        &'static Orbit,
+        Option<&'static Parent>,
+    ),
+>;
+
+fn select_body(
+    trigger: Trigger<SelectBody>,
+    mut selection_query: Query<(Entity, &mut PickSelection)>,
+    mut commands: Commands,
+) {
+    let target = trigger.event().0;
+    for (entity, mut selection) in &mut selection_query {
+        let selected = entity == target;
+        if selection.is_selected != selected {
+            selection.is_selected = selected;
+        }
+    }
+    commands.trigger(RefocusCamera);
+}
+
+/// Escape backs out of the current selection first, and only pauses the game once there's
+/// nothing left selected
+fn deselect_or_pause(
+    mut selection_query: Query<&mut PickSelection>,
+    mut next_gameplay: ResMut<NextState<Gameplay>>,
+) {
+    let mut deselected = false;
+    for mut selection in &mut selection_query {
+        if selection.is_selected {
+            selection.is_selected = false;
+            deselected = true;
+        }
+    }
+    if !deselected {
+        next_gameplay.set(Gameplay::Paused);
+    }
+}
+
+fn centre_on_sun(sun_query: Query<Entity, With<Sun>>, mut commands: Commands) {
+    if let Ok(sun) = sun_query.get_single() {
+        commands.trigger(SelectBody(sun));
+    }
+}
+
+/// Tab and Shift-Tab or the left and right arrows step between neighbouring planets, or moons of
+/// the same planet. The down arrow steps into a planet's moons and the up arrow back out again
+fn navigate_bodies(
+    input: Res<ButtonInput<KeyCode>>,
+    body_query: BodyQuery,
+    selection_query: Query<&PickSelection>,
+    mut commands: Commands,
+) {
+    let shift = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let step = if input.just_pressed(KeyCode::Tab) {
+        Some(if shift { -1 } else { 1 })
+    } else if input.just_pressed(KeyCode::ArrowRight) {
+        Some(1)
+    } else if input.just_pressed(KeyCode::ArrowLeft) {
+        Some(-1)
+    } else {
+        None
+    };
+    // With Ctrl or Shift held the number keys are camera bookmarks instead
+    let bookmarking = input.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+    ]);
+    let numbered = NUMBER_KEYS
+        .iter()
+        .position(|key| input.just_pressed(*key))
+        .filter(|_| !bookmarking);
+    let into_moons = input.just_pressed(KeyCode::ArrowDown);
+    let out_of_moons = input.just_pressed(KeyCode::ArrowUp);
+    if step.is_none() && numbered.is_none() && !into_moons && !out_of_moons {
+        return;
+    }
+
+    let selected = body_query
+        .iter()
+        .find(|(entity, ..)| {
+            selection_query
+                .get(*entity)
+                .is_ok_and(|selection| selection.is_selected)
+        })
+        .map(|(entity, planet, _, parent)| (entity, planet.is_moon, parent.map(Parent::get)));
+    let planets = orbital_order(&body_query, None);
+
+    let target = match numbered {
+        Some(index) => planets.get(index).copied(),
+        None if into_moons => selected
+            .filter(|(_, is_moon, _)| !is_moon)
+            .and_then(|(planet, _, _)| orbital_order(&body_query, Some(planet)).first().copied()),
+        None if out_of_moons => selected
+            .filter(|(_, is_moon, _)| *is_moon)
+            .and_then(|(_, _, parent)| parent),
+        None => {
+            let step = step.unwrap_or(1);
+            match selected {
+                // Moons cycle around their own planet
+                Some((moon, true, parent)) => {
+                    neighbour(&orbital_order(&body_query, parent), Some(moon), step)
+                }
+                Some((planet, false, _)) => neighbour(&planets, Some(planet), step),
+                None => neighbour(&planets, None, step),
+            }
+        }
+    };
+    if let Some(target) = target {
+        commands.trigger(SelectBody(target));
+    }
+}
+
+/// Returns the planets, or moons of the provided planet, sorted from the innermost orbit out
+fn orbital_order(body_query: &BodyQuery, around: Option<Entity>) -> Vec<Entity> {
+    let mut bodies: Vec<(Entity, f32)> = body_query
+        .iter()
+        .filter(|(_, planet, _, parent)| {
+            if planet.is_moon {
+                around.is_some() && parent.map(Parent::get) == around
+            } else {
+                around.is_none()
+            }
+        })
+        .map(|(entity, _, orbit, _)| (entity, orbit.radius()))
+        .collect();
+    bodies.sort_by(|a, b| a.1.total_cmp(&b.1));
+    bodies.into_iter().map(|(entity, _)| entity).collect()
+}
+
+/// Returns the body `step` places along from the current one, wrapping around at either end.
+/// With nothing selected yet, stepping forwards starts from the first and backwards from the last
+fn neighbour(bodies: &[Entity], current: Option<Entity>, step: isize) -> Option<Entity> {
+    if bodies.is_empty() {
+        return None;
+    }
+    let index = match current.and_then(|current| bodies.iter().position(|body| *body == current)) {
+        Some(index) => index.cast_signed() + step,
+        None if step < 0 => -1,
+        None => 0,
+    };
+    Some(bodies[index.rem_euclid(bodies.len().cast_signed()).cast_unsigned()])
+}
@@ -0,0 +1,90 @@
+//! Messages telling the player about things happening around the solar system, kept in a log they
+//! can look back through.
+
+use bevy::prelude::*;
+use derive_more::derive::Display;
+
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<NotificationLog>();
+    app.add_systems(OnEnter(Screen::Playing), clear_notification_log);
+    app.observe(record_notification);
+}
+
+/// The most entries kept in the log before the oldest are dropped
+static LOG_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Display)]
+pub enum NotificationCategory {
+    Flare,
+    Research,
+    Power,
+    Resources,
+    Logistics,
+}
+
+impl NotificationCategory {
+    pub const ALL: [Self; 5] = [
+        Self::Flare,
+        Self::Research,
+        Self::Power,
+        Self::Resources,
+        Self::Logistics,
+    ];
+
+    pub const fn color(self) -> Color {
+        match self {
+            Self::Flare => Color::srgb(1., 0.6, 0.2),
+            Self::Research => Color::srgb(0.6, 0.8, 1.),
+            Self::Power => Color::srgb(1., 0.9, 0.3),
+            Self::Resources => Color::srgb(0.6, 1., 0.6),
+            Self::Logistics => Color::srgb(0.85, 0.7, 1.),
+        }
+    }
+}
+
+/// Trigger this event from anywhere to tell the player something
+#[derive(Event, Debug, Clone)]
+pub struct Notify {
+    pub category: NotificationCategory,
+    pub message: String,
+    /// The planet the message is about, which the player can jump to from it
+    pub planet: Option<Entity>,
+}
+
+impl Notify {
+    pub fn new(category: NotificationCategory, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            message: message.into(),
+            planet: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn about(mut self, planet: Entity) -> Self {
+        self.planet = Some(planet);
+        self
+    }
+}
+
+/// Every notification so far this game, oldest first
+#[derive(Resource, Debug, Default)]
+pub struct NotificationLog {
+    pub entries: Vec<Notify>,
+}
+
+fn clear_notification_log(mut log: ResMut<NotificationLog>) {
+    log.entries.clear();
+}
+
+fn record_notification(trigger: Trigger<Notify>, mut log: ResMut<NotificationLog>) {
+    let notification = trigger.event().clone();
+    info!("[{}] {}", notification.category, notification.message);
+    log.entries.push(notification);
+    if log.entries.len() > LOG_LENGTH {
+        let excess = log.entries.len() - LOG_LENGTH;
+        log.entries.drain(..excess);
+    }
+}
@@ -1,15 +1,23 @@
 use bevy::{
     app::{App, Update},
     core::Name,
+    input::common_conditions::input_just_pressed,
     math::{Quat, Vec3Swizzles},
     prelude::*,
-    sprite::{Material2d, MaterialMesh2dBundle},
+    sprite::{Material2d, MaterialMesh2dBundle, Mesh2dHandle},
     time::Time,
 };
 
-use crate::screen::Screen;
+use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
 
-use super::spawn::planets::PlanetShadow;
+use crate::screen::{Gameplay, Screen};
+
+use super::{
+    resources::StationType,
+    settings::Settings,
+    spawn::planets::{OrbitRing, PlanetShadow, MESH_RESOLUTION, ONE_AU},
+};
 
 #[derive(Component, Debug)]
 pub struct PlanetNameLabel;
@@ -20,10 +28,85 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (move_things_with_orbits, move_planet_shadows)
             .chain()
-            .run_if(in_state(Screen::Playing)),
+            .run_if(in_state(Gameplay::Running)),
+    );
+    app.add_systems(
+        Update,
+        (
+            resize_orbit_rings.run_if(in_state(Screen::Playing)),
+            toggle_distance_view
+                .run_if(in_state(Gameplay::Running).and_then(input_just_pressed(KeyCode::KeyV))),
+        ),
     );
 }
 
+/// Distances out from the sun inside this are stretched out the most in the logarithmic view
+static LOG_VIEW_KNEE: f32 = 0.3;
+
+/// How distances out from the sun are drawn. Only where things are drawn changes, anything that
+/// depends on how far from the sun something really is works the same in either view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, Serialize, Deserialize)]
+pub enum DistanceView {
+    #[default]
+    #[display("True Scale")]
+    TrueScale,
+    /// Squeezes the outer planets in towards the inner ones so the whole system can be seen at
+    /// once. Earth stays where it is
+    Logarithmic,
+}
+
+impl DistanceView {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::TrueScale => Self::Logarithmic,
+            Self::Logarithmic => Self::TrueScale,
+        }
+    }
+
+    /// Distance from the sun that something really this far away is drawn at
+    pub fn view_distance(self, distance: f32) -> f32 {
+        match self {
+            Self::TrueScale => distance,
+            Self::Logarithmic => log_view_scale() * (distance / (*ONE_AU * LOG_VIEW_KNEE)).ln_1p(),
+        }
+    }
+
+    /// How far from the sun something drawn at the provided distance really is
+    pub fn true_distance(self, view_distance: f32) -> f32 {
+        match self {
+            Self::TrueScale => view_distance,
+            Self::Logarithmic => {
+                *ONE_AU * LOG_VIEW_KNEE * (view_distance / log_view_scale()).exp_m1()
+            }
+        }
+    }
+
+    /// How much further something is drawn as moving than it really moves, at the provided true
+    /// distance from the sun
+    pub fn stretch(self, distance: f32) -> f32 {
+        match self {
+            Self::TrueScale => 1.,
+            Self::Logarithmic => log_view_scale() / ONE_AU.mul_add(LOG_VIEW_KNEE, distance),
+        }
+    }
+
+    /// Where something really at the provided position relative to the sun is drawn
+    pub fn view_position(self, position: Vec2) -> Vec2 {
+        position.normalize_or_zero() * self.view_distance(position.length())
+    }
+
+    /// Where something drawn at the provided position relative to the sun really is
+    pub fn true_position(self, view_position: Vec2) -> Vec2 {
+        view_position.normalize_or_zero() * self.true_distance(view_position.length())
+    }
+}
+
+/// Scales the logarithmic view so one AU out is drawn at one AU
+fn log_view_scale() -> f32 {
+    *ONE_AU / LOG_VIEW_KNEE.recip().ln_1p()
+}
+
 #[derive(Component)]
 pub struct Transient;
 
@@ -37,10 +120,71 @@ fn clear_transients(mut commands: Commands, query: Query<Entity, With<Transient>
 pub struct Planet {
     pub is_moon: bool,
     pub has_magnetic_field: bool,
+    pub atmosphere: Atmosphere,
     pub size: f32,
+    /// Flare energy that has reached the planet. Only `Planet::exposed_power` of it makes it down
+    /// to any given station
     pub absorbed_power: f32,
 }
 
+impl Planet {
+    /// Returns false if nothing gets through to stations of the provided type, either because of a
+    /// magnetic field or because the atmosphere shields them
+    pub fn is_exposed(&self, station: StationType) -> bool {
+        !self.has_magnetic_field && self.atmosphere.is_exposed(station)
+    }
+
+    /// Returns the flare energy that has got through to stations of the provided type
+    pub fn exposed_power(&self, station: StationType) -> f32 {
+        if self.is_exposed(station) {
+            self.absorbed_power * self.atmosphere.exposure(station)
+        } else {
+            0.
+        }
+    }
+}
+
+/// How much atmosphere a planet has, which decides how much flare energy reaches its surface
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Display, Deserialize)]
+pub enum Atmosphere {
+    #[default]
+    None,
+    Thin,
+    Standard,
+    Thick,
+}
+
+/// Stations exposed to less than this fraction of a flare's energy are considered shielded
+static EXPOSURE_THRESHOLD: f32 = 0.1;
+
+impl Atmosphere {
+    /// Returns the fraction of flare energy that makes it through to the surface
+    pub const fn transmission(self) -> f32 {
+        match self {
+            Self::None => 1.,
+            Self::Thin => 0.6,
+            Self::Standard => 0.25,
+            Self::Thick => 0.05,
+        }
+    }
+
+    /// Returns the fraction of flare energy that a station of the provided type is exposed to
+    pub fn exposure(self, station: StationType) -> f32 {
+        match station {
+            StationType::Surface => self.transmission(),
+            // Ocean platforms have the water above them as well as the atmosphere
+            StationType::Ocean => self.transmission() * 0.5,
+            // Orbital stations sit above the atmosphere, so they always take the full hit
+            StationType::Orbit => 1.,
+        }
+    }
+
+    /// Returns true if a station of the provided type takes meaningful damage from flares
+    pub fn is_exposed(self, station: StationType) -> bool {
+        self.exposure(station) >= EXPOSURE_THRESHOLD
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct Orbit {
     radius: f32,
@@ -59,6 +203,12 @@ impl Orbit {
         }
     }
 
+    /// Returns the same orbit, starting the provided number of degrees further around
+    pub const fn starting_at(mut self, degrees: f32) -> Self {
+        self.degrees = degrees;
+        self
+    }
+
     pub fn increment_orbit(&mut self, passed: f32) {
         self.degrees += 360. * (passed / self.period);
     }
@@ -67,6 +217,38 @@ impl Orbit {
         self.degrees
     }
 
+    /// Returns the semi-major axis of the orbit
+    pub const fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub const fn period(&self) -> f32 {
+        self.period
+    }
+
+    /// Returns the angular speed in degrees per second
+    pub fn angular_speed(&self) -> f32 {
+        360. / self.period
+    }
+
+    /// Returns the distance from the parent body at the provided angle, measured from periapsis
+    pub fn radius_at(&self, degrees: f32) -> f32 {
+        self.radius * self.eccentricity.mul_add(-self.eccentricity, 1.)
+            / self.eccentricity.mul_add(degrees.to_radians().cos(), 1.)
+    }
+
+    /// Returns the position relative to the parent body after the provided number of seconds
+    pub fn predict(&self, seconds: f32) -> Vec2 {
+        let mut future = Self {
+            radius: self.radius,
+            eccentricity: self.eccentricity,
+            degrees: self.degrees,
+            period: self.period,
+        };
+        future.increment_orbit(seconds);
+        future.to_x_y().into()
+    }
+
     pub fn to_x_y(&self) -> (f32, f32) {
         if self.eccentricity > 0. {
             unimplemented!()
@@ -107,12 +289,46 @@ pub struct PlanetBundle<M: Material2d> {
 
 fn move_things_with_orbits(
     time: Res<Time>,
-    mut planet_query: Query<(&mut Transform, &mut Orbit), With<Orbit>>,
+    settings: Res<Settings>,
+    mut planet_query: Query<(&mut Transform, &mut Orbit, Has<Parent>), With<Orbit>>,
 ) {
-    for (mut transform, mut orbit) in &mut planet_query {
+    for (mut transform, mut orbit, has_parent) in &mut planet_query {
         orbit.increment_orbit(time.delta_seconds());
-        let (x, y) = orbit.to_x_y();
-        transform.translation.x = x;
-        transform.translation.y = y;
+        let mut position = Vec2::from(orbit.to_x_y());
+        // Only distances out from the sun change with the view, not those around a planet
+        if !has_parent {
+            position = settings.distance_view.view_position(position);
+        }
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Redraw the orbits around the sun whenever the distance view changes
+fn resize_orbit_rings(
+    settings: Res<Settings>,
+    mut drawn_view: Local<Option<DistanceView>>,
+    ring_query: Query<(Ref<OrbitRing>, &Mesh2dHandle), Without<Parent>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let view = settings.distance_view;
+    let view_changed = *drawn_view != Some(view);
+    *drawn_view = Some(view);
+    for (ring, mesh) in &ring_query {
+        if view_changed || ring.is_added() {
+            let radius = view.view_distance(ring.radius);
+            meshes.insert(
+                &mesh.0,
+                Annulus::new(radius - ring.half_width, radius + ring.half_width)
+                    .mesh()
+                    .resolution(MESH_RESOLUTION)
+                    .build(),
+            );
+        }
     }
 }
+
+fn toggle_distance_view(mut settings: ResMut<Settings>) {
+    settings.distance_view = settings.distance_view.next();
+    settings.save();
+}
@@ -0,0 +1,297 @@
+//! Colonists living on planets, who staff the structures built there.
+
+use bevy::prelude::*;
+
+use crate::screen::{Gameplay, Screen};
+
+use super::{
+    planets::Planet,
+    resources::{
+        cost_calculator, ConsumingStructure, EnabledStructure, HarvestedResources, RawResourceType,
+        StationType,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(build_habitat);
+    app.add_systems(
+        Update,
+        (update_population_caps, grow_population, flare_casualties)
+            .chain()
+            .run_if(in_state(Gameplay::Running)),
+    );
+}
+
+/// Fraction of the population added per second while there is room to grow
+static GROWTH_RATE: f32 = 0.02;
+/// Colonists arriving per second at any planet with room for them
+static IMMIGRATION_RATE: f32 = 5.;
+/// Fraction per second of the population above the cap that is lost
+static DECLINE_RATE: f32 = 0.05;
+/// Colonists lost per unit of flare energy that reaches the surface
+static FLARE_CASUALTIES: f32 = 0.05;
+
+static HABITAT_CAPACITY: f32 = 500.;
+static HABITAT_COST: (f32, f32) = (300., 400.);
+static HABITAT_COST_SCALING: f32 = 1.25;
+static HABITAT_OXYGEN: f32 = 0.002;
+static HABITAT_WATER: f32 = 0.001;
+
+#[derive(Component, Debug, Default)]
+pub struct Population {
+    count: f32,
+    natural_cap: f32,
+    cap: f32,
+    habitats: usize,
+    absorbed_seen: f32,
+}
+
+impl Population {
+    /// A population on a planet that can support `natural_cap` colonists without any habitats
+    pub const fn native(count: f32, natural_cap: f32) -> Self {
+        Self {
+            count,
+            natural_cap,
+            cap: natural_cap,
+            habitats: 0,
+            absorbed_seen: 0.,
+        }
+    }
+
+    /// Returns the number of colonists currently living on the planet
+    pub const fn count(&self) -> f32 {
+        self.count
+    }
+
+    /// Returns the number of colonists the planet can currently support
+    pub const fn cap(&self) -> f32 {
+        self.cap
+    }
+
+    /// Returns the (metals, silicate) cost of building the next habitat on this planet
+    pub fn habitat_cost(&self) -> (f32, f32) {
+        (
+            cost_calculator(HABITAT_COST.0, self.habitats, HABITAT_COST_SCALING),
+            cost_calculator(HABITAT_COST.1, self.habitats, HABITAT_COST_SCALING),
+        )
+    }
+}
+
+/// The number of colonists a structure needs to run
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Workforce(pub f32);
+
+/// A structure that raises the population cap of its planet while it is supplied
+#[derive(Component, Debug)]
+pub struct Habitat {
+    pub planet: Entity,
+    pub capacity: f32,
+}
+
+/// Trigger this event to build a habitat on the provided planet
+#[derive(Event, Debug)]
+pub struct BuildHabitat {
+    pub planet: Entity,
+}
+
+fn build_habitat(
+    trigger: Trigger<BuildHabitat>,
+    mut resources: ResMut<HarvestedResources>,
+    mut population_query: Query<&mut Population>,
+    mut commands: Commands,
+) {
+    let planet = trigger.event().planet;
+    let Ok(mut population) = population_query.get_mut(planet) else {
+        return;
+    };
+    let cost = population.habitat_cost();
+    if resources.metals < cost.0 || resources.silicate < cost.1 {
+        return;
+    }
+    resources.metals -= cost.0;
+    resources.silicate -= cost.1;
+    population.habitats += 1;
+
+    commands
+        .spawn((
+            Name::new("Habitat"),
+            Habitat {
+                planet,
+                capacity: HABITAT_CAPACITY,
+            },
+            // Colonists need air to breathe and water to drink
+            ConsumingStructure(vec![
+                (HABITAT_OXYGEN, RawResourceType::Oxygen),
+                (HABITAT_WATER, RawResourceType::Hydrogen),
+            ]),
+            EnabledStructure(false),
+            SpatialBundle::default(),
+            StateScoped(Screen::Playing),
+        ))
+        .set_parent(planet);
+}
+
+fn update_population_caps(
+    habitat_query: Query<(&Habitat, &EnabledStructure)>,
+    mut population_query: Query<&mut Population>,
+) {
+    for mut population in &mut population_query {
+        population.cap = population.natural_cap;
+    }
+    for (habitat, enabled) in &habitat_query {
+        if enabled.0 {
+            if let Ok(mut population) = population_query.get_mut(habitat.planet) {
+                population.cap += habitat.capacity;
+            }
+        }
+    }
+}
+
+fn grow_population(time: Res<Time>, mut population_query: Query<&mut Population>) {
+    let delta = time.delta_seconds();
+    for mut population in &mut population_query {
+        if population.count < population.cap {
+            let growth = GROWTH_RATE * population.count * (1. - population.count / population.cap);
+            population.count = (growth + IMMIGRATION_RATE)
+                .mul_add(delta, population.count)
+                .min(population.cap);
+        } else {
+            // Without enough habitats people leave or die off, but not all at once
+            let excess = population.count - population.cap;
+            population.count -= excess * DECLINE_RATE * delta;
+        }
+    }
+}
+
+/// Flares that get through to the surface of a planet without a magnetic field kill colonists
+fn flare_casualties(mut planet_query: Query<(&Planet, &mut Population)>) {
+    for (planet, mut population) in &mut planet_query {
+        let exposed = planet.exposed_power(StationType::Surface);
+        let absorbed = exposed - population.absorbed_seen;
+        population.absorbed_seen = exposed;
+        if absorbed <= 0. {
+            continue;
+        }
+        let lost = absorbed * FLARE_CASUALTIES;
+        population.count = (population.count - lost).max(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::game::planets::Atmosphere;
+
+    fn population_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        app.add_systems(
+            Update,
+            (update_population_caps, grow_population, flare_casualties).chain(),
+        );
+        app
+    }
+
+    fn step(app: &mut App, seconds: f32) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    fn population(app: &App, planet: Entity) -> &Population {
+        app.world()
+            .get::<Population>(planet)
+            .expect("Planet has a population")
+    }
+
+    fn test_planet(has_magnetic_field: bool, absorbed_power: f32) -> Planet {
+        Planet {
+            is_moon: false,
+            has_magnetic_field,
+            atmosphere: Atmosphere::None,
+            size: 10.,
+            absorbed_power,
+        }
+    }
+
+    #[test]
+    fn population_grows_logistically_towards_the_cap() {
+        let mut app = population_app();
+        let planet = app.world_mut().spawn(Population::native(100., 1_000.)).id();
+        step(&mut app, 1.);
+        // 2% of 100, slowed by being a tenth of the way to the cap, plus the immigrants
+        let expected = 0.02f32.mul_add(100. * 0.9, 5.) + 100.;
+        assert!((population(&app, planet).count() - expected).abs() < 0.001);
+
+        app.world_mut()
+            .get_mut::<Population>(planet)
+            .expect("Planet has a population")
+            .count = 999.;
+        step(&mut app, 10.);
+        assert!((population(&app, planet).count() - 1_000.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn population_above_the_cap_declines() {
+        let mut app = population_app();
+        let planet = app
+            .world_mut()
+            .spawn(Population::native(1_500., 1_000.))
+            .id();
+        step(&mut app, 1.);
+        assert!((population(&app, planet).count() - 1_475.).abs() < 0.001);
+    }
+
+    #[test]
+    fn supplied_habitats_raise_the_cap() {
+        let mut app = population_app();
+        let planet = app.world_mut().spawn(Population::native(0., 1_000.)).id();
+        let habitat = app
+            .world_mut()
+            .spawn((
+                Habitat {
+                    planet,
+                    capacity: HABITAT_CAPACITY,
+                },
+                EnabledStructure(true),
+            ))
+            .id();
+        step(&mut app, 0.);
+        assert!((population(&app, planet).cap() - 1_500.).abs() < f32::EPSILON);
+
+        app.world_mut()
+            .get_mut::<EnabledStructure>(habitat)
+            .expect("Habitat exists")
+            .0 = false;
+        step(&mut app, 0.);
+        assert!((population(&app, planet).cap() - 1_000.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn flares_kill_colonists_once() {
+        let mut app = population_app();
+        let planet = app
+            .world_mut()
+            .spawn((test_planet(false, 100.), Population::native(1_000., 1_000.)))
+            .id();
+        step(&mut app, 0.);
+        let lost = 100. * FLARE_CASUALTIES;
+        assert!((population(&app, planet).count() - (1_000. - lost)).abs() < 0.001);
+        step(&mut app, 0.);
+        assert!((population(&app, planet).count() - (1_000. - lost)).abs() < 0.001);
+    }
+
+    #[test]
+    fn magnetic_field_protects_colonists() {
+        let mut app = population_app();
+        let planet = app
+            .world_mut()
+            .spawn((test_planet(true, 100.), Population::native(1_000., 1_000.)))
+            .id();
+        step(&mut app, 0.);
+        assert!((population(&app, planet).count() - 1_000.).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,101 @@
+//! Power priorities for structures, and an overview of the grid they draw from.
+
+use bevy::{prelude::*, utils::HashSet};
+use derive_more::derive::Display;
+
+use crate::screen::Screen;
+
+use super::notifications::{NotificationCategory, Notify};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PowerGrid>();
+    app.add_systems(OnEnter(Screen::Playing), reset_power_grid);
+    app.add_systems(
+        Update,
+        announce_unpowered
+            .run_if(in_state(Screen::Playing).and_then(resource_changed::<PowerGrid>)),
+    );
+    app.observe(cycle_power_priority);
+}
+
+/// The order structures get power in when there isn't enough for all of them
+#[derive(Component, Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Display)]
+pub enum PowerPriority {
+    Critical,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl PowerPriority {
+    /// Returns the priority after this one, wrapping back around to the first
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Critical => Self::Normal,
+            Self::Normal => Self::Low,
+            Self::Low => Self::Critical,
+        }
+    }
+}
+
+/// The fraction of its power draw a structure received this frame, which its output is scaled by
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Deref)]
+pub struct PowerSupply(pub f32);
+
+impl Default for PowerSupply {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Power produced and drawn across every structure this frame
+#[derive(Resource, Debug, Default)]
+pub struct PowerGrid {
+    pub supply: f32,
+    pub demand: f32,
+    pub delivered: f32,
+    /// Structures that were ready to run but didn't get any power
+    pub unpowered: Vec<Entity>,
+}
+
+/// Trigger this event to move a structure to the next power priority
+#[derive(Event, Debug)]
+pub struct CyclePowerPriority {
+    pub structure: Entity,
+}
+
+fn reset_power_grid(mut grid: ResMut<PowerGrid>) {
+    *grid = PowerGrid::default();
+}
+
+fn cycle_power_priority(
+    trigger: Trigger<CyclePowerPriority>,
+    mut priority_query: Query<&mut PowerPriority>,
+) {
+    if let Ok(mut priority) = priority_query.get_mut(trigger.event().structure) {
+        *priority = priority.next();
+    }
+}
+
+/// Tell the player about structures as they lose power, but not again every frame they stay off
+fn announce_unpowered(
+    grid: Res<PowerGrid>,
+    structure_query: Query<(&Name, Option<&Parent>)>,
+    mut announced: Local<HashSet<Entity>>,
+    mut commands: Commands,
+) {
+    announced.retain(|entity| grid.unpowered.contains(entity));
+    for entity in &grid.unpowered {
+        if !announced.insert(*entity) {
+            continue;
+        }
+        let Ok((name, parent)) = structure_query.get(*entity) else {
+            continue;
+        };
+        let notification = Notify::new(NotificationCategory::Power, format!("{name} unpowered"));
+        commands.trigger(match parent {
+            Some(parent) => notification.about(parent.get()),
+            None => notification,
+        });
+    }
+}
@@ -2,11 +2,19 @@ use std::vec::Vec;
 
 use bevy::{prelude::*, utils::HashMap};
 use derive_more::derive::Display;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{screen::Screen, ui::multi_progress_bar::MultiProgressBar, utils::format_number};
+use crate::{screen::Gameplay, ui::multi_progress_bar::MultiProgressBar, utils::format_number};
 
 use super::{
+    harvesters::harvester_cost,
+    logistics::{LocalStockpile, LogisticsSettings},
+    maintenance::Wear,
+    notifications::{NotificationCategory, Notify},
+    planets::DistanceView,
+    population::{Population, Workforce},
+    power::{PowerGrid, PowerPriority, PowerSupply},
+    settings::Settings,
     spawn::planets::ONE_AU,
     sun::Sun,
     unlocks::{TechUnlocks, Technology},
@@ -14,6 +22,7 @@ use super::{
 
 pub(super) fn plugin(app: &mut App) {
     app.insert_resource(HarvestedResources::default());
+    app.observe(announce_depletion);
     app.add_systems(
         PreUpdate,
         (
@@ -27,7 +36,7 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (consuming_structures, producing_structures)
             .chain()
-            .run_if(in_state(Screen::Playing)),
+            .run_if(in_state(Gameplay::Running)),
     );
 }
 
@@ -55,6 +64,25 @@ impl StationType {
             Self::Orbit => (400., 50.),
         }
     }
+
+    /// Returns the power a harvester of this type draws to run
+    pub const fn power_draw(self) -> f32 {
+        match self {
+            Self::Surface => 0.5,
+            Self::Ocean => 0.8,
+            Self::Orbit => 1.2,
+        }
+    }
+
+    /// Returns the number of colonists needed to run a harvester of this type. Orbital stations
+    /// are automated
+    pub const fn workforce(self) -> f32 {
+        match self {
+            Self::Surface => 100.,
+            Self::Ocean => 200.,
+            Self::Orbit => 0.,
+        }
+    }
 }
 
 #[derive(Debug, Component, Clone, Copy, Eq, PartialEq)]
@@ -69,7 +97,7 @@ pub struct ResourceBarTextLabel;
 #[derive(Debug, Component, Clone, PartialEq, Eq)]
 pub struct ResourceCostLabel(pub RawResourceType);
 
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Serialize)]
 pub struct HarvestedResources {
     pub metals: f32,
     pub silicate: f32,
@@ -89,6 +117,23 @@ impl HarvestedResources {
         }
     }
 
+    /// Returns true if there is enough of every resource in the provided cost
+    pub fn can_afford(&self, cost: &[(f32, RawResourceType)]) -> bool {
+        cost.iter()
+            .all(|(amount, resource)| self.get(*resource) >= *amount)
+    }
+
+    /// Removes the provided cost if it can be afforded, returns a bool indicating success
+    pub fn spend(&mut self, cost: &[(f32, RawResourceType)]) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+        for (amount, resource) in cost {
+            *self.get_mut(*resource) -= *amount;
+        }
+        true
+    }
+
     pub fn get_mut(&mut self, res_type: RawResourceType) -> &mut f32 {
         match res_type {
             RawResourceType::Metals => &mut self.metals,
@@ -139,6 +184,10 @@ pub struct RawResource {
     station_type: StationType,
     levels: Vec<(f32, Technology)>,
     consumed: f32,
+    /// Set once everything unlocked so far has been harvested, until more gets unlocked. Not
+    /// known until the first harvest, so a deposit that's loaded already used up isn't announced
+    #[serde(skip)]
+    depleted: Option<bool>,
 }
 
 impl RawResource {
@@ -152,6 +201,7 @@ impl RawResource {
             station_type,
             levels,
             consumed: 0.,
+            depleted: None,
         }
     }
 
@@ -160,6 +210,16 @@ impl RawResource {
         self.resource_type
     }
 
+    /// Return the `StationType` needed to harvest this resource
+    pub const fn station_type(&self) -> StationType {
+        self.station_type
+    }
+
+    /// Returns the total amount reachable at each level, along with the technology that unlocks it
+    pub fn levels(&self) -> &[(f32, Technology)] {
+        &self.levels
+    }
+
     /// Scale the provided percentage values from creation (0.0-1.0) with the planet size
     pub fn apply_scale(&mut self, size: f32) {
         self.levels.iter_mut().for_each(|pair| pair.0 *= size);
@@ -176,6 +236,11 @@ impl RawResource {
             .map(|(value, _tech)| *value)
     }
 
+    /// Returns true if the first level of this resource has been unlocked
+    pub fn is_unlocked(&self, techs: &TechUnlocks) -> bool {
+        self.get_current(techs).is_some()
+    }
+
     /// Returns the maximum unlockable resource amount from the last resource level
     fn get_last(&self) -> f32 {
         self.levels.last().expect("Minimum 1 level").0
@@ -207,6 +272,22 @@ impl RawResource {
         self.consumed += consumed;
     }
 
+    /// Returns true if everything unlocked so far has been harvested
+    pub fn is_exhausted(&self, techs: &TechUnlocks) -> bool {
+        self.is_unlocked(techs) && self.get_available(techs) <= 0.
+    }
+
+    /// Harvest up to the requested amount, returning how much was actually available and whether
+    /// that used up everything unlocked so far
+    fn harvest(&mut self, requested: f32, techs: &TechUnlocks) -> (f32, bool) {
+        let was_depleted = self.depleted.unwrap_or_else(|| self.is_exhausted(techs));
+        let harvested = requested.clamp(0., self.get_available(techs).max(0.));
+        self.increment_consumed(harvested);
+        let depleted = self.is_exhausted(techs);
+        self.depleted = Some(depleted);
+        (harvested, depleted && !was_depleted)
+    }
+
     /// Gets the percentage of each (consumed, avalible, unlockable) as a f32 between 0.0 and 1.0
     pub fn get_ratios(&self, techs: &TechUnlocks) -> (f32, f32, f32) {
         let Some(current_unlocked) = self.get_current(techs) else {
@@ -224,6 +305,17 @@ impl RawResource {
     /// unlockable
     pub fn get_ratios_text(&self, techs: &TechUnlocks) -> String {
         let (consumed, available, unlockable) = self.get_ratios(techs);
+        if self.is_exhausted(techs) {
+            return self.get_next(techs).map_or_else(
+                || "Exhausted".to_string(),
+                |tech| {
+                    format!(
+                        "Exhausted\nResearch {} for more",
+                        tech.to_formatted_string()
+                    )
+                },
+            );
+        }
         let mut out = if consumed > 0. {
             format!(
                 "{}% Consumed\n{}% Available",
@@ -269,11 +361,59 @@ pub struct ProducingStructure {
     pub sun_buff: f32,
 }
 
+impl ProducingStructure {
+    /// Returns how much the structure produces this frame at full power and condition, taking
+    /// its real distance from the sun into account if it depends on it
+    pub fn output(&self, transform: &GlobalTransform, sun: &Sun, view: DistanceView) -> f32 {
+        if self.sun_buff == 0. {
+            return self.produced;
+        }
+        let distance = view.true_distance(transform.translation().xy().length());
+        // Structures spawned this frame haven't been given their position yet
+        if distance == 0. {
+            return 0.;
+        }
+        self.produced * ((self.sun_buff * sun.power_scale()) / (distance / *ONE_AU))
+    }
+}
+
 #[allow(clippy::cast_precision_loss)]
 pub fn cost_calculator(cost: f32, number: usize, mult: f32) -> f32 {
     cost * mult.powf(number as f32)
 }
 
+/// Triggered when a planet runs out of a resource that has been unlocked so far
+#[derive(Event, Debug)]
+pub struct ResourceDepleted {
+    pub planet: Entity,
+    pub resource: RawResourceType,
+}
+
+fn announce_depletion(
+    trigger: Trigger<ResourceDepleted>,
+    tech: Res<TechUnlocks>,
+    planet_query: Query<(&Name, &PlanetResources)>,
+    mut commands: Commands,
+) {
+    let ResourceDepleted { planet, resource } = *trigger.event();
+    let Ok((name, planet_resources)) = planet_query.get(planet) else {
+        return;
+    };
+    let next = planet_resources
+        .get(resource)
+        .and_then(|raw| raw.get_next(&tech));
+    let message = next.map_or_else(
+        || format!("{resource} on {name} has been exhausted"),
+        |tech| {
+            format!(
+                "{resource} on {name} has been exhausted, research {} to reach more",
+                tech.to_formatted_string()
+            )
+        },
+    );
+    commands.trigger(Notify::new(NotificationCategory::Resources, message).about(planet));
+}
+
 #[derive(Debug, Component, Default)]
 pub struct BuiltHarvesters(pub HashMap<RawResourceType, Vec<Entity>>);
 
@@ -328,102 +468,208 @@ impl Requirement for ConsumingStructure {
     }
 }
 
+/// Returns true if there is any of the resource left on the planet for the structure to harvest.
+/// Whatever is left can still be harvested, even if it's less than a full frame's worth
+fn has_resource_to_harvest(
+    producing: &ProducingStructure,
+    tech: &TechUnlocks,
+    planet_resources_query: &Query<&PlanetResources>,
+) -> bool {
+    producing
+        .planet
+        .and_then(|entity| planet_resources_query.get(entity).ok())
+        .and_then(|resources| resources.get(producing.res_type))
+        .map_or(true, |res| res.get_available(tech) > 0.)
+}
+
+/// Returns the fraction of its draw every structure in a priority gets. When there isn't enough
+/// power for the whole priority, every structure in it gets the same share and runs at reduced
+/// output instead of some shutting off completely
+fn priority_share(available: f32, demand: f32) -> f32 {
+    if demand > 0. {
+        (available / demand).clamp(0., 1.)
+    } else {
+        1.
+    }
+}
+
+// FIXME: Fix the too many lines issue by breaking this up
+#[allow(clippy::too_many_lines)]
 fn consuming_structures(
     tech: Res<TechUnlocks>,
-    sun: Query<&Sun>,
     planet_resources_query: Query<&PlanetResources>,
+    population_query: Query<&Population>,
     mut resources: ResMut<HarvestedResources>,
+    mut grid: ResMut<PowerGrid>,
+    priority_query: Query<(Entity, Option<&PowerPriority>), With<EnabledStructure>>,
     mut structure_query: Query<(
         Option<&PoweredStructure>,
         Option<&ConsumingStructure>,
         Option<&ProducingStructure>,
+        Option<&Workforce>,
         &mut EnabledStructure,
-        &GlobalTransform,
+        Option<&mut PowerSupply>,
     )>,
 ) {
-    let Ok(sun) = sun.get_single() else {
-        return; // TODO: Probably return an error here
-    };
-
-    for (power, consumed_res, producing, mut enabled, transform) in &mut structure_query {
-        let has_resource = producing.map_or(true, |producing| {
-            producing
-                .planet
-                .and_then(|entity| planet_resources_query.get(entity).ok())
-                .map_or_else(
-                    || true,
-                    |resources| {
-                        let produced = if producing.sun_buff == 0. {
-                            producing.produced
-                        } else {
-                            producing.produced
-                                * ((producing.sun_buff * sun.power_scale())
-                                    / (transform.translation().distance(Vec3::ZERO) / *ONE_AU))
-                        };
-                        resources
-                            .get(producing.res_type)
-                            .map_or(true, |res| res.get_available(&tech) >= produced)
-                    },
-                )
-        });
-        if power.is_some() || consumed_res.is_some() {
-            if has_resource
-                && power.map_or(true, |power| power.check(&resources))
-                && consumed_res.map_or(true, |consumed| consumed.check(&resources))
+    // Structures get power in priority order, then by entity within each priority, so the same
+    // ones miss out every frame there isn't enough to go around
+    let mut order: Vec<(PowerPriority, Entity)> = priority_query
+        .iter()
+        .map(|(entity, priority)| (priority.copied().unwrap_or_default(), entity))
+        .collect();
+    order.sort_unstable();
+
+    // Colonists on each planet that haven't been assigned to a structure yet this frame
+    let mut free_workers: HashMap<Entity, f32> = HashMap::new();
+    grid.demand = 0.;
+    grid.delivered = 0.;
+    grid.unpowered.clear();
+
+    for group in order.chunk_by(|a, b| a.0 == b.0) {
+        // Structures in this priority that have everything they need to run except power, along
+        // with their draw and the workers they've been assigned
+        let mut waiting: Vec<(Entity, f32, Option<(Entity, f32)>)> = Vec::new();
+        for &(_, entity) in group {
+            let Ok((power, consumed_res, producing, workforce, mut enabled, supply)) =
+                structure_query.get_mut(entity)
+            else {
+                continue;
+            };
+            assert!(
+                power.is_some() || consumed_res.is_some(),
+                "EnabledStructure does not have PoweredStructure or ConsumingStructure. One of the two is required."
+            );
+            let has_resource = producing.map_or(true, |producing| {
+                has_resource_to_harvest(producing, &tech, &planet_resources_query)
+            });
+            let staffing = workforce
+                .zip(producing.and_then(|producing| producing.planet))
+                .map(|(workforce, planet)| (planet, workforce.0));
+            let has_workers = workforce.is_none()
+                || staffing.is_some_and(|(planet, needed)| {
+                    *free_workers.entry(planet).or_insert_with(|| {
+                        population_query.get(planet).map_or(0., Population::count)
+                    }) >= needed
+                });
+            if !(has_resource
+                && has_workers
+                && consumed_res.map_or(true, |consumed| consumed.check(&resources)))
             {
-                if let Some(power) = power {
-                    power.consume(&mut resources);
+                enabled.0 = false;
+                if let Some(mut supply) = supply {
+                    supply.0 = 0.;
+                }
+                continue;
+            }
+            if let Some((planet, needed)) = staffing {
+                if let Some(free) = free_workers.get_mut(&planet) {
+                    *free -= needed;
                 }
+            }
+            if let Some(power) = power {
+                waiting.push((entity, power.0, staffing));
+            } else {
                 if let Some(consumed_res) = consumed_res {
                     consumed_res.consume(&mut resources);
                 }
                 enabled.0 = true;
-            } else {
-                enabled.0 = false;
             }
-        } else {
-            panic!("EnabledStructure does not have PoweredStructure or ConsumingStructure. One of the two is required.");
+        }
+
+        let demand: f32 = waiting.iter().map(|(_, draw, _)| draw).sum();
+        let fraction = priority_share(resources.power, demand);
+        resources.power -= demand * fraction;
+        grid.demand += demand;
+        grid.delivered += demand * fraction;
+
+        for (entity, draw, staffing) in waiting {
+            let Ok((_, consumed_res, _, _, mut enabled, supply)) = structure_query.get_mut(entity)
+            else {
+                continue;
+            };
+            let running = fraction > 0.
+                && consumed_res.map_or(true, |consumed| consumed.consume(&mut resources));
+            enabled.0 = running;
+            if let Some(mut supply) = supply {
+                supply.0 = if running { fraction } else { 0. };
+            }
+            if !running {
+                // Give back what it would have used so the next priority can have it
+                resources.power += draw * fraction;
+                grid.delivered -= draw * fraction;
+                grid.unpowered.push(entity);
+                if let Some((planet, needed)) = staffing {
+                    if let Some(free) = free_workers.get_mut(&planet) {
+                        *free += needed;
+                    }
+                }
+            }
         }
     }
 }
 
 fn producing_structures(
     tech: Res<TechUnlocks>,
+    logistics: Res<LogisticsSettings>,
+    settings: Res<Settings>,
     sun: Query<&Sun>,
     mut resources: ResMut<HarvestedResources>,
+    mut grid: ResMut<PowerGrid>,
     structure_query: Query<(
         Option<&EnabledStructure>,
         &ProducingStructure,
         &GlobalTransform,
+        Option<&Wear>,
+        Option<&PowerSupply>,
     )>,
     mut planet_resources_query: Query<&mut PlanetResources>,
+    mut stockpile_query: Query<&mut LocalStockpile>,
+    mut commands: Commands,
 ) {
     let Ok(sun) = sun.get_single() else {
         return; // TODO: Probably throw an error here
     };
 
-    for (_, producing, transform) in structure_query
+    grid.supply = 0.;
+    for (_, producing, transform, wear, supply) in structure_query
         .iter()
-        .filter(|(enabled, _, _)| enabled.map_or(true, |enabled| enabled.0))
+        .filter(|(enabled, _, _, _, _)| enabled.map_or(true, |enabled| enabled.0))
     {
-        let produced = if producing.sun_buff == 0. {
-            producing.produced
-        } else {
-            producing.produced
-                * ((producing.sun_buff * sun.power_scale())
-                    / (transform.translation().distance(Vec3::ZERO) / *ONE_AU))
-        };
-        if let Some(mut planet_res) = producing
+        let produced = producing.output(transform, sun, settings.distance_view);
+        // Worn down structures only produce a fraction of what they used to, and browned out
+        // ones only as much as the power they got allows
+        let mut produced =
+            produced * wear.map_or(1., Wear::condition) * supply.map_or(1., |supply| supply.0);
+        if producing.res_type == RawResourceType::Power {
+            grid.supply += produced;
+        }
+        // Harvesters can only take what's left in the deposit
+        let deposit = producing
             .planet
-            .and_then(|entity| planet_resources_query.get_mut(entity).ok())
-        {
+            .and_then(|planet| Some((planet, planet_resources_query.get_mut(planet).ok()?)));
+        if let Some((planet, mut planet_res)) = deposit {
             if let Some(res) = planet_res.get_mut(producing.res_type) {
-                if res.get_available(&tech) >= produced {
-                    res.increment_consumed(produced);
+                let (harvested, depleted) = res.harvest(produced, &tech);
+                produced = harvested;
+                if depleted {
+                    commands.trigger(ResourceDepleted {
+                        planet,
+                        resource: producing.res_type,
+                    });
                 }
             }
         }
-        *resources.get_mut(producing.res_type) += produced;
+        // With logistics enabled, anything harvested away from the home world has to be shipped
+        // there before it can be spent. Power is distributed instantly either way
+        let stockpile = producing
+            .planet
+            .filter(|_| logistics.enabled && producing.res_type != RawResourceType::Power)
+            .and_then(|entity| stockpile_query.get_mut(entity).ok());
+        if let Some(mut stockpile) = stockpile {
+            *stockpile.0.get_mut(producing.res_type) += produced;
+        } else {
+            *resources.get_mut(producing.res_type) += produced;
+        }
     }
 }
 
@@ -480,10 +726,7 @@ fn update_planet_resource_buy_cost_labels(
     for (mut text, planet_res, cost_type) in &mut label_query {
         if let Ok((resources, harvesters)) = planet_query.get(planet_res.0) {
             if let Some(resource) = resources.get(planet_res.1) {
-                let mut cost = resource.cost(&tech);
-                let modifier = harvesters.0.get(&resource.name()).map_or(0, Vec::len);
-                cost.0 = cost_calculator(cost.0, modifier, 0.001);
-                cost.1 = cost_calculator(cost.1, modifier, 0.001);
+                let cost = harvester_cost(resource, harvesters, &tech);
                 match cost_type.0 {
                     RawResourceType::Metals => text.sections[0].value = format_number(cost.0),
                     RawResourceType::Silicate => text.sections[0].value = format_number(cost.1),
@@ -493,3 +736,190 @@ fn update_planet_resource_buy_cost_labels(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn power_app(power: f32) -> App {
+        let mut app = App::new();
+        app.init_resource::<TechUnlocks>();
+        app.init_resource::<PowerGrid>();
+        app.insert_resource(HarvestedResources {
+            power,
+            ..Default::default()
+        });
+        app.add_systems(Update, consuming_structures);
+        app
+    }
+
+    fn spawn_powered(app: &mut App, priority: PowerPriority, draw: f32) -> Entity {
+        app.world_mut()
+            .spawn((
+                PoweredStructure(draw),
+                EnabledStructure(false),
+                priority,
+                PowerSupply::default(),
+            ))
+            .id()
+    }
+
+    fn deposit(amount: f32) -> RawResource {
+        RawResource::new(
+            RawResourceType::Metals,
+            StationType::Surface,
+            vec![
+                (amount, Technology::None),
+                (amount * 2., Technology::Orbitals),
+            ],
+        )
+    }
+
+    /// Keeps count of every `ResourceDepleted` triggered
+    #[derive(Resource, Debug, Default)]
+    struct Depletions(usize);
+
+    fn harvest_app(resource: RawResource) -> App {
+        let mut app = App::new();
+        app.init_resource::<TechUnlocks>();
+        app.init_resource::<PowerGrid>();
+        app.init_resource::<HarvestedResources>();
+        app.init_resource::<LogisticsSettings>();
+        app.init_resource::<Settings>();
+        app.init_resource::<Depletions>();
+        app.observe(
+            |_trigger: Trigger<ResourceDepleted>, mut depletions: ResMut<Depletions>| {
+                depletions.0 += 1;
+            },
+        );
+        app.add_systems(Update, producing_structures);
+        app.world_mut().spawn(Sun::default());
+        let planet = app
+            .world_mut()
+            .spawn(PlanetResources::new(vec![resource]))
+            .id();
+        app.world_mut().spawn((
+            ProducingStructure {
+                planet: Some(planet),
+                res_type: RawResourceType::Metals,
+                produced: 0.4,
+                sun_buff: 0.,
+            },
+            GlobalTransform::default(),
+        ));
+        app
+    }
+
+    #[test]
+    fn harvest_is_clamped_to_what_is_available() {
+        let techs = TechUnlocks::default();
+        let mut resource = deposit(1.);
+        assert_eq!(resource.harvest(0.75, &techs), (0.75, false));
+        assert_eq!(resource.harvest(-1., &techs), (0., false));
+        assert_eq!(resource.harvest(0.75, &techs), (0.25, true));
+        assert_eq!(resource.harvest(0.75, &techs), (0., false));
+        assert!((resource.get_consumed() - 1.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn depleted_again_after_more_is_unlocked() {
+        let mut techs = TechUnlocks::default();
+        let mut resource = deposit(1.);
+        assert!(resource.harvest(1., &techs).1);
+        assert!(techs.unlock(Technology::Orbitals));
+        assert_eq!(resource.harvest(0.5, &techs), (0.5, false));
+        assert_eq!(resource.harvest(1., &techs), (0.5, true));
+    }
+
+    #[test]
+    fn loaded_deposit_that_is_already_used_up_is_not_announced() {
+        let mut resource: RawResource = ron::from_str(
+            "(resource_type: Metals, station_type: Surface, levels: [(1.0, None)], consumed: 1.0)",
+        )
+        .expect("Resource spec is valid");
+        assert_eq!(resource.harvest(1., &TechUnlocks::default()), (0., false));
+
+        let mut app = harvest_app(resource);
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Depletions>().0, 0);
+    }
+
+    #[test]
+    fn depletion_is_announced_once() {
+        let mut app = harvest_app(deposit(1.));
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Depletions>().0, 1);
+        assert!((app.world().resource::<HarvestedResources>().metals - 1.).abs() < 0.001);
+    }
+
+    fn supply(app: &App, structure: Entity) -> f32 {
+        app.world()
+            .get::<PowerSupply>(structure)
+            .expect("Structure exists")
+            .0
+    }
+
+    #[test]
+    fn share_covers_whole_demand_when_there_is_enough() {
+        assert!((priority_share(10., 4.) - 1.).abs() < f32::EPSILON);
+        assert!((priority_share(3., 4.) - 0.75).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn share_without_supply_or_demand() {
+        assert!(priority_share(0., 4.).abs() < f32::EPSILON);
+        assert!(priority_share(-1., 4.).abs() < f32::EPSILON);
+        assert!((priority_share(0., 0.) - 1.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn critical_is_served_before_normal_and_low() {
+        let mut app = power_app(3.);
+        let low = spawn_powered(&mut app, PowerPriority::Low, 1.);
+        let normal = spawn_powered(&mut app, PowerPriority::Normal, 2.);
+        let critical = spawn_powered(&mut app, PowerPriority::Critical, 2.);
+        app.update();
+        assert!((supply(&app, critical) - 1.).abs() < f32::EPSILON);
+        assert!((supply(&app, normal) - 0.5).abs() < f32::EPSILON);
+        assert!(supply(&app, low).abs() < f32::EPSILON);
+        assert_eq!(app.world().resource::<PowerGrid>().unpowered, vec![low]);
+    }
+
+    #[test]
+    fn underpowered_priority_gets_an_equal_fraction() {
+        let mut app = power_app(3.);
+        let small = spawn_powered(&mut app, PowerPriority::Normal, 2.);
+        let large = spawn_powered(&mut app, PowerPriority::Normal, 4.);
+        app.update();
+        assert!((supply(&app, small) - 0.5).abs() < f32::EPSILON);
+        assert!((supply(&app, large) - 0.5).abs() < f32::EPSILON);
+        assert!(app.world().resource::<HarvestedResources>().power.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn nothing_runs_without_power() {
+        let mut app = power_app(0.);
+        let critical = spawn_powered(&mut app, PowerPriority::Critical, 1.);
+        let normal = spawn_powered(&mut app, PowerPriority::Normal, 1.);
+        app.update();
+        for structure in [critical, normal] {
+            assert!(supply(&app, structure).abs() < f32::EPSILON);
+            assert!(!app.world().get::<EnabledStructure>(structure).unwrap().0);
+        }
+        assert_eq!(app.world().resource::<PowerGrid>().unpowered.len(), 2);
+    }
+
+    #[test]
+    fn structures_without_draw_run_at_full_output() {
+        let mut app = power_app(2.);
+        let idle = spawn_powered(&mut app, PowerPriority::Normal, 0.);
+        app.update();
+        assert!((supply(&app, idle) - 1.).abs() < f32::EPSILON);
+        assert!(app.world().get::<EnabledStructure>(idle).unwrap().0);
+        assert!((app.world().resource::<HarvestedResources>().power - 2.).abs() < f32::EPSILON);
+    }
+}
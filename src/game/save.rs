@@ -0,0 +1,35 @@
+//! Saving the game from the pause menu. For now that's the settings and the home world's
+//! stockpile, nothing reads the stockpile back in yet.
+
+use bevy::prelude::*;
+
+use super::{
+    resources::HarvestedResources,
+    settings::{write_saved, Settings},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(save_game);
+}
+
+/// What the stockpile is saved as, alongside the settings
+static SAVE_NAME: &str = "save";
+
+/// Trigger this event to save the game
+#[derive(Event, Debug)]
+pub struct SaveGame;
+
+fn save_game(
+    _trigger: Trigger<SaveGame>,
+    settings: Res<Settings>,
+    resources: Res<HarvestedResources>,
+) {
+    settings.save();
+    match ron::ser::to_string_pretty(&*resources, ron::ser::PrettyConfig::default()) {
+        Ok(serialized) => {
+            write_saved(SAVE_NAME, &serialized);
+            info!("Game saved");
+        }
+        Err(error) => warn!("Couldn't save the game: {error}"),
+    }
+}
@@ -0,0 +1,183 @@
+//! Player preferences for audio and graphics, along with camera bookmarks, kept between sessions
+//! in a settings file on native builds and in the browser's local storage on the web.
+
+use bevy::{audio::Volume, core_pipeline::bloom::BloomSettings, prelude::*};
+use derive_more::derive::Display;
+
+use super::{camera::CameraBookmarks, planets::DistanceView};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Settings::load());
+    app.add_systems(
+        Update,
+        (apply_volume, apply_bloom, apply_ui_scale).run_if(resource_changed::<Settings>),
+    );
+}
+
+/// Volume everything is played at with the master volume all the way up
+static BASE_VOLUME: f32 = 0.3;
+/// What settings are saved as, see `read_saved` for where that ends up
+static SETTINGS_NAME: &str = "settings";
+/// Prefix for the local storage keys things are saved under on the web
+#[cfg(target_family = "wasm")]
+static STORAGE_PREFIX: &str = "angry_sun_";
+
+/// How many particles each solar flare is made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, Serialize, Deserialize)]
+pub enum FlareDensity {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl FlareDensity {
+    /// Flares are split into this many times fewer particles than at full density
+    pub const fn divisor(self) -> u8 {
+        match self {
+            Self::Low => 4,
+            Self::Medium => 2,
+            Self::High => 1,
+        }
+    }
+
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Low => Self::Medium,
+            Self::Medium => Self::High,
+            Self::High => Self::Low,
+        }
+    }
+}
+
+/// Options the player can change from the settings menu. Anything missing from a saved file, such
+/// as settings added since it was written, falls back to its default
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub bloom: bool,
+    pub bloom_intensity: f32,
+    pub ui_scale: f32,
+    pub flare_density: FlareDensity,
+    /// Seconds the camera takes to fly to whatever's selected
+    pub camera_flight_time: f32,
+    pub distance_view: DistanceView,
+    /// Saved with Ctrl and a number key in game rather than from the settings menu
+    pub camera_bookmarks: CameraBookmarks,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 1.,
+            sfx_volume: 1.,
+            bloom: true,
+            bloom_intensity: BloomSettings::default().intensity,
+            ui_scale: 1.,
+            flare_density: FlareDensity::default(),
+            camera_flight_time: 1.,
+            distance_view: DistanceView::default(),
+            camera_bookmarks: CameraBookmarks::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// The volume every sound is scaled by
+    pub fn global_volume(&self) -> f32 {
+        BASE_VOLUME * self.master_volume
+    }
+
+    /// Read the saved settings, or the defaults if there aren't any or they can't be read
+    pub fn load() -> Self {
+        let Some(saved) = read_saved(SETTINGS_NAME) else {
+            return Self::default();
+        };
+        ron::from_str(&saved).unwrap_or_else(|error| {
+            warn!("Couldn't read saved settings, using the defaults: {error}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(serialized) => write_saved(SETTINGS_NAME, &serialized),
+            Err(error) => warn!("Couldn't save settings: {error}"),
+        }
+    }
+}
+
+/// Returns what was last saved under the provided name. Native builds keep it in a RON file next
+/// to wherever the game was run from, the web in the browser's local storage
+#[cfg(not(target_family = "wasm"))]
+pub fn read_saved(name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{name}.ron")).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub fn write_saved(name: &str, serialized: &str) {
+    let path = format!("{name}.ron");
+    if let Err(error) = std::fs::write(&path, serialized) {
+        warn!("Couldn't save {name} to {path}: {error}");
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(target_family = "wasm")]
+pub fn read_saved(name: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{STORAGE_PREFIX}{name}"))
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_family = "wasm")]
+pub fn write_saved(name: &str, serialized: &str) {
+    let key = format!("{STORAGE_PREFIX}{name}");
+    let saved = local_storage().is_some_and(|storage| storage.set_item(&key, serialized).is_ok());
+    if !saved {
+        warn!("Couldn't save {name} to local storage");
+    }
+}
+
+/// New sounds pick up the global volume when they start, sounds already playing are updated by
+/// their audio bus
+fn apply_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    global_volume.volume = Volume::new(settings.global_volume());
+}
+
+fn apply_bloom(
+    settings: Res<Settings>,
+    mut camera_query: Query<(Entity, Option<&mut BloomSettings>), With<Camera>>,
+    mut commands: Commands,
+) {
+    for (camera, bloom) in &mut camera_query {
+        match (settings.bloom, bloom) {
+            (true, Some(mut bloom)) => bloom.intensity = settings.bloom_intensity,
+            (true, None) => {
+                commands.entity(camera).insert(BloomSettings {
+                    intensity: settings.bloom_intensity,
+                    ..Default::default()
+                });
+            }
+            (false, Some(_)) => {
+                commands.entity(camera).remove::<BloomSettings>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+fn apply_ui_scale(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = settings.ui_scale;
+}
@@ -17,9 +17,12 @@ use serde::Deserialize;
 
 use crate::{
     game::{
-        camera::{ClearFinishZoomEvent, FinishZoom, ScaleWithZoom},
+        camera::{FocusZoom, RefocusCamera, ScaleWithZoom},
+        harvesters::starting_solar_array,
         highlight::{HighlightObject, LinkSelectionObject},
-        planets::{Orbit, Planet, PlanetBundle},
+        logistics::{HomeWorld, LocalStockpile},
+        planets::{Atmosphere, Orbit, Planet, PlanetBundle},
+        population::Population,
         resources::{BuiltHarvesters, PlanetResources, RawResource, RawResourceType, StationType},
         sun::Sun,
         unlocks::Technology,
@@ -38,17 +41,24 @@ pub(super) fn plugin(app: &mut App) {
 pub struct PlanetShadow;
 
 #[derive(Component, Debug)]
-pub struct OrbitRing;
+pub struct OrbitRing {
+    /// Real distance of the orbit from whatever it goes around
+    pub radius: f32,
+    pub half_width: f32,
+}
 
-static MESH_RESOLUTION: usize = 100;
+pub static MESH_RESOLUTION: usize = 100;
 
 static PLANET_SCALE: f32 = 12.;
 pub static RADIUS_SCALE: f32 = 0.1;
 static MOON_SCALE: f32 = 7.;
 static MOON_RADIUS_SCALE: f32 = 1.5;
 
+/// Real distance of the outermost planet from the sun
 pub static LAST_PLANET_DISTANCE: LazyLock<f32> =
-    LazyLock::new(|| scale(4_530_000_000. * RADIUS_SCALE * 0.7));
+    LazyLock::new(|| scale(4_530_000_000. * RADIUS_SCALE));
+/// Real distance of Jupiter from the sun, the furthest planet flares reach
+pub static JUPITER_DISTANCE: LazyLock<f32> = LazyLock::new(|| scale(780_000_000. * RADIUS_SCALE));
 
 fn spawn_solar_system(
     _trigger: Trigger<SpawnSolarSystem>,
@@ -137,7 +147,7 @@ fn spawn_sun(
         MaterialMesh2dBundle {
             mesh: Mesh2dHandle(
                 meshes.add(
-                    Circle::new(scale(1_400_000.))
+                    Circle::new(*SUN_SIZE)
                         .mesh()
                         .resolution(MESH_RESOLUTION)
                         .build(),
@@ -152,7 +162,7 @@ fn spawn_sun(
         },
         ScaleWithZoom { ratio: 0.1 },
         PickableBundle::default(),
-        FinishZoom::new_with_target(35.),
+        FocusZoom(35.),
         PlanetResources::new(vec![RawResource::new(
             RawResourceType::Hydrogen,
             StationType::Orbit,
@@ -175,8 +185,7 @@ fn spawn_mercury<A: Material2d>(
         meshes,
         materials,
         Name::new("Mercury"),
-        scale(57_000_000. * RADIUS_SCALE * 1.2), // We've adjusted mercury specifically because
-        // it's so close to the sun
+        scale(57_000_000. * RADIUS_SCALE),
         scale(4_879. * PLANET_SCALE),
         88.,
         Color::srgb(183. / 255., 184. / 255., 185. / 255.),
@@ -185,6 +194,7 @@ fn spawn_mercury<A: Material2d>(
         vec![],
         false,
         false,
+        Atmosphere::None,
         Some(2.5),
         PlanetResources::new(vec![
             RawResource::new(
@@ -228,6 +238,7 @@ fn spawn_venus<A: Material2d>(
         vec![],
         false,
         false,
+        Atmosphere::Thick,
         None,
         PlanetResources::new(vec![
             RawResource::new(
@@ -251,7 +262,10 @@ fn spawn_venus<A: Material2d>(
 }
 
 pub static ONE_AU: LazyLock<f32> = LazyLock::new(|| scale(149_000_000. * RADIUS_SCALE));
+pub static SUN_SIZE: LazyLock<f32> = LazyLock::new(|| scale(1_400_000.));
 
+// FIXME: Fix the too many lines issue by breaking this up
+#[allow(clippy::too_many_lines)]
 fn spawn_earth<A: Material2d>(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -273,6 +287,7 @@ fn spawn_earth<A: Material2d>(
         vec![],
         true,
         false,
+        Atmosphere::None,
         Some(0.5),
         PlanetResources::new(vec![
             RawResource::new(
@@ -301,7 +316,7 @@ fn spawn_earth<A: Material2d>(
             ),
         ]),
     );
-    spawn_planet(
+    let earth = spawn_planet(
         commands,
         meshes,
         materials,
@@ -315,6 +330,7 @@ fn spawn_earth<A: Material2d>(
         moon,
         false,
         true,
+        Atmosphere::Standard,
         None,
         PlanetResources::new(vec![
             RawResource::new(
@@ -352,7 +368,16 @@ fn spawn_earth<A: Material2d>(
                 ],
             ),
         ]),
-    );
+    )[0];
+    // Earth starts out with people on it, and enough power to put them to work. It's also where
+    // everything gets shipped to, so its stockpile is the global one
+    commands
+        .entity(earth)
+        .insert((Population::native(2_000., 10_000.), HomeWorld))
+        .remove::<LocalStockpile>();
+    commands
+        .spawn(starting_solar_array(earth))
+        .set_parent(earth);
 }
 
 fn spawn_mars<A: Material2d>(
@@ -376,6 +401,7 @@ fn spawn_mars<A: Material2d>(
         vec![],
         false,
         false,
+        Atmosphere::Thin,
         None,
         PlanetResources::new(vec![
             RawResource::new(
@@ -419,7 +445,7 @@ fn spawn_jupiter<A: Material2d>(
         meshes,
         materials,
         Name::new("Jupiter"),
-        scale(780_000_000. * RADIUS_SCALE * 0.8),
+        *JUPITER_DISTANCE,
         scale(143_000. * PLANET_SCALE),
         4_330.6 * 0.8,
         Color::srgb(148. / 255., 105. / 255., 86. / 255.),
@@ -428,6 +454,7 @@ fn spawn_jupiter<A: Material2d>(
         vec![],
         false,
         true,
+        Atmosphere::Thick,
         Some(0.3),
         PlanetResources::new(vec![RawResource::new(
             RawResourceType::Hydrogen,
@@ -450,7 +477,7 @@ fn spawn_saturn<A: Material2d>(
         meshes,
         materials,
         Name::new("Saturn"),
-        scale(1_437_000_000. * RADIUS_SCALE * 0.8),
+        scale(1_437_000_000. * RADIUS_SCALE),
         scale(120_536. * PLANET_SCALE),
         10_756. * 0.8,
         Color::srgb(206. / 255., 184. / 255., 184. / 255.),
@@ -459,6 +486,7 @@ fn spawn_saturn<A: Material2d>(
         vec![],
         false,
         true,
+        Atmosphere::Thick,
         Some(0.3),
         PlanetResources::new(vec![RawResource::new(
             RawResourceType::Hydrogen,
@@ -481,7 +509,7 @@ fn spawn_uranus<A: Material2d>(
         meshes,
         materials,
         Name::new("Uranus"),
-        scale(2_871_000_000. * RADIUS_SCALE * 0.7),
+        scale(2_871_000_000. * RADIUS_SCALE),
         scale(51_118. * PLANET_SCALE),
         30_687. * 0.7,
         Color::srgb(172. / 255., 229. / 255., 238. / 255.),
@@ -490,6 +518,7 @@ fn spawn_uranus<A: Material2d>(
         vec![],
         false,
         true,
+        Atmosphere::Thick,
         Some(0.6),
         PlanetResources::new(vec![RawResource::new(
             RawResourceType::Hydrogen,
@@ -521,6 +550,7 @@ fn spawn_neptune<A: Material2d>(
         vec![],
         false,
         true,
+        Atmosphere::Thick,
         Some(0.6),
         PlanetResources::new(vec![RawResource::new(
             RawResourceType::Hydrogen,
@@ -538,6 +568,7 @@ struct PlanetSpec {
     orbital_period: f32,
     color: (u8, u8, u8),
     magnetic_field: bool,
+    atmosphere: Atmosphere,
     moon: bool,
     zoom_scale: Option<f32>,
     resources: PlanetResources,
@@ -554,7 +585,10 @@ fn make_orbit_ring<A: Material2d>(
     hidden: bool,
 ) -> impl Bundle {
     (
-        OrbitRing,
+        OrbitRing {
+            radius,
+            half_width: width * width_modifier,
+        },
         MaterialMesh2dBundle {
             mesh: Mesh2dHandle(
                 meshes.add(
@@ -580,7 +614,7 @@ fn make_orbit_ring<A: Material2d>(
         LinkSelectionObject(planet),
         PickableBundle::default(),
         On::<Pointer<Click>>::commands_mut(|_input, commands: &mut Commands| {
-            commands.trigger(ClearFinishZoomEvent);
+            commands.trigger(RefocusCamera);
         }),
     )
 }
@@ -601,6 +635,7 @@ fn spawn_planet<A: Material2d>(
     children: Vec<Entity>,
     moon: bool,
     magnetic_field: bool,
+    atmosphere: Atmosphere,
     zoom_scale: Option<f32>,
     mut resources: PlanetResources,
 ) -> Vec<Entity> {
@@ -616,6 +651,7 @@ fn spawn_planet<A: Material2d>(
             planet: Planet {
                 is_moon: moon,
                 has_magnetic_field: magnetic_field,
+                atmosphere,
                 size: scaled_size,
                 absorbed_power: 0.,
             },
@@ -636,14 +672,16 @@ fn spawn_planet<A: Material2d>(
         },
         StateScoped(Screen::Playing),
         BuiltHarvesters::default(),
+        Population::default(),
+        LocalStockpile::default(),
         ScaleWithZoom {
             ratio: zoom_scale.unwrap_or(1.),
         },
         PickableBundle::default(),
-        FinishZoom::new_with_target(15. / zoom_scale.unwrap_or(1.)),
+        FocusZoom(15. / zoom_scale.unwrap_or(1.)),
         resources,
         On::<Pointer<Click>>::commands_mut(|_input, commands: &mut Commands| {
-            commands.trigger(ClearFinishZoomEvent);
+            commands.trigger(RefocusCamera);
         }),
     ));
     planet.with_children(|parent| {
@@ -667,7 +705,7 @@ fn spawn_planet<A: Material2d>(
             PickableBundle::default(),
             LinkSelectionObject(parent.parent_entity()),
             On::<Pointer<Click>>::commands_mut(|_input, commands: &mut Commands| {
-                commands.trigger(ClearFinishZoomEvent);
+                commands.trigger(RefocusCamera);
             }),
         ));
 
@@ -677,7 +715,7 @@ fn spawn_planet<A: Material2d>(
             PickableBundle::default(),
             LinkSelectionObject(parent.parent_entity()),
             On::<Pointer<Click>>::commands_mut(|_input, commands: &mut Commands| {
-                commands.trigger(ClearFinishZoomEvent);
+                commands.trigger(RefocusCamera);
             }),
             MaterialMesh2dBundle {
                 mesh: Mesh2dHandle(
@@ -0,0 +1,129 @@
+//! Orbital stations, which circle the planet they harvest from instead of sitting on its surface.
+
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::HashMap,
+};
+use bevy_mod_picking::{
+    events::{Click, Pointer},
+    prelude::On,
+    PickableBundle,
+};
+
+use crate::screen::Screen;
+
+use super::{
+    camera::RefocusCamera,
+    highlight::LinkSelectionObject,
+    markers::{HarvesterMarker, MarkerResources},
+    planets::{Orbit, Planet},
+    resources::StationType,
+    spawn::planets::SUN_SIZE,
+    sun::Sun,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, build_station_mesh);
+    app.add_systems(
+        Update,
+        place_orbital_stations.run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Orbit radius of stations as a multiple of their planet's size
+static STATION_ORBIT: f32 = 1.5;
+/// Size of stations as a multiple of their planet's size
+static STATION_SIZE: f32 = 0.15;
+/// Seconds it takes a station to go around its planet once
+static STATION_PERIOD: f32 = 6.;
+/// Degrees between each station and the next one built around the same planet
+static STATION_SPACING: f32 = 137.5;
+
+/// A harvester that sits in orbit around the body it harvests from. Flares that hit it don't reach
+/// the body underneath
+#[derive(Component, Debug)]
+pub struct OrbitalStation {
+    pub planet: Entity,
+    size: f32,
+    absorbed_power: f32,
+}
+
+impl OrbitalStation {
+    pub const fn new(planet: Entity) -> Self {
+        Self {
+            planet,
+            size: 0.,
+            absorbed_power: 0.,
+        }
+    }
+
+    /// Returns the distance from the centre of the station a flare has to come within to hit it
+    pub const fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Returns the total flare energy that has hit this station
+    pub const fn absorbed_power(&self) -> f32 {
+        self.absorbed_power
+    }
+
+    pub fn absorb(&mut self, power: f32) {
+        self.absorbed_power += power;
+    }
+}
+
+#[derive(Resource)]
+struct StationMesh(Handle<Mesh>);
+
+fn build_station_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(StationMesh(meshes.add(Circle::new(1.))));
+}
+
+/// Give newly built orbital stations their own orbit around the planet, spread out from the
+/// stations that are already there
+fn place_orbital_stations(
+    station_mesh: Res<StationMesh>,
+    marker_resources: Res<MarkerResources>,
+    mut new_station_query: Query<(Entity, &mut OrbitalStation), Without<Orbit>>,
+    placed_query: Query<&OrbitalStation, With<Orbit>>,
+    planet_query: Query<(Option<&Planet>, Has<Sun>)>,
+    mut commands: Commands,
+) {
+    // Stations built this frame need spreading out from each other as well
+    let mut placed: HashMap<Entity, usize> = HashMap::new();
+    for station in &placed_query {
+        *placed.entry(station.planet).or_default() += 1;
+    }
+    for (entity, mut station) in &mut new_station_query {
+        // Stellar lifting stations orbit the sun, which isn't a planet
+        let body_size = match planet_query.get(station.planet) {
+            Ok((Some(planet), _)) => planet.size,
+            Ok((None, true)) => *SUN_SIZE,
+            _ => continue,
+        };
+        let placed = placed.entry(station.planet).or_default();
+        station.size = body_size * STATION_SIZE;
+
+        #[allow(clippy::cast_precision_loss)]
+        let orbit = Orbit::circle(body_size * STATION_ORBIT, STATION_PERIOD)
+            .starting_at(*placed as f32 * STATION_SPACING);
+        *placed += 1;
+        let (x, y) = orbit.to_x_y();
+        commands.entity(entity).insert((
+            orbit,
+            HarvesterMarker::new(StationType::Orbit),
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(station_mesh.0.clone()),
+                material: marker_resources.material(StationType::Orbit, false),
+                transform: Transform::from_xyz(x, y, 2.).with_scale(Vec3::splat(station.size)),
+                ..Default::default()
+            },
+            PickableBundle::default(),
+            LinkSelectionObject(station.planet),
+            On::<Pointer<Click>>::commands_mut(|_input, commands: &mut Commands| {
+                commands.trigger(RefocusCamera);
+            }),
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::Div;
 use bevy::prelude::*;
 use rand::{thread_rng, Rng};
 
-use crate::screen::Screen;
+use crate::screen::{Gameplay, Screen};
 
 use super::flare::SpawnFlare;
 
@@ -12,7 +12,7 @@ pub(super) fn plugin(app: &mut App) {
         PreUpdate,
         update_sun_labels.run_if(in_state(Screen::Playing)),
     );
-    app.add_systems(Update, (update_sun).run_if(in_state(Screen::Playing)));
+    app.add_systems(Update, (update_sun).run_if(in_state(Gameplay::Running)));
 }
 
 #[derive(Debug, Component)]
@@ -0,0 +1,295 @@
+//! Hohmann transfer windows between orbiting bodies, and an overlay showing the next one between
+//! the last two selected bodies.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::PickSelection;
+
+use crate::{screen::Screen, utils::format_number};
+
+use super::{planets::Orbit, settings::Settings};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TransferSelection>();
+    app.add_systems(OnEnter(Screen::Playing), spawn_transfer_label);
+    app.add_systems(OnExit(Screen::Playing), clear_transfer_selection);
+    app.add_systems(
+        Update,
+        (
+            track_transfer_selection,
+            (draw_transfer_overlay, update_transfer_label),
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Each transfer calculation is refined this many times to account for elliptical orbits, where
+/// the radius at departure and arrival depends on when the transfer starts
+static REFINEMENT_STEPS: usize = 4;
+static OVERLAY_SEGMENTS: usize = 64;
+static OVERLAY_COLOR: Color = Color::srgb(0.4, 0.9, 1.);
+
+/// The next opportunity to make a Hohmann transfer from one body to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferWindow {
+    /// Seconds until the transfer should start
+    pub wait: f32,
+    /// Seconds the transfer takes once started
+    pub duration: f32,
+    /// Angle in degrees the origin is at when the transfer starts
+    pub departure_angle: f32,
+    pub departure_radius: f32,
+    pub arrival_radius: f32,
+}
+
+impl TransferWindow {
+    /// Returns the point on the transfer orbit a fraction (0.0-1.0) of the way through the transfer
+    pub fn point(&self, fraction: f32) -> Vec2 {
+        let (r1, r2) = (self.departure_radius, self.arrival_radius);
+        let semi_major = f32::midpoint(r1, r2);
+        let eccentricity = (r2 - r1).abs() / (r1 + r2);
+        // Leaving from the inner orbit starts the transfer at periapsis, leaving from the outer
+        // one starts it at apoapsis
+        let (periapsis, start) = if r1 <= r2 {
+            (self.departure_angle.to_radians(), 0.)
+        } else {
+            (self.departure_angle.to_radians() + PI, PI)
+        };
+        let anomaly = fraction.mul_add(PI, start);
+        let radius = semi_major * eccentricity.mul_add(-eccentricity, 1.)
+            / eccentricity.mul_add(anomaly.cos(), 1.);
+        Vec2::from_angle(periapsis + anomaly) * radius
+    }
+}
+
+/// Returns the time a Hohmann transfer between two orbits takes. Orbital periods in the game
+/// don't strictly follow Kepler's laws, so half the transfer orbit's period is estimated from
+/// each orbit separately and the two are averaged
+fn transfer_duration(from: &Orbit, to: &Orbit, r1: f32, r2: f32) -> f32 {
+    let semi_major = f32::midpoint(r1, r2);
+    let from_estimate = from.period() * (semi_major / from.radius()).powf(1.5);
+    let to_estimate = to.period() * (semi_major / to.radius()).powf(1.5);
+    (from_estimate * to_estimate).sqrt() / 2.
+}
+
+/// Calculates the next Hohmann transfer window between two bodies orbiting the same parent.
+/// Returns `None` if the two orbits never change phase relative to each other
+pub fn next_transfer_window(from: &Orbit, to: &Orbit) -> Option<TransferWindow> {
+    let relative_speed = to.angular_speed() - from.angular_speed();
+    if relative_speed == 0. {
+        return None;
+    }
+    let synodic_period = 360. / relative_speed.abs();
+    let current_phase = to.angle() - from.angle();
+
+    let mut window = TransferWindow {
+        wait: 0.,
+        duration: 0.,
+        departure_angle: from.angle(),
+        departure_radius: from.radius(),
+        arrival_radius: to.radius(),
+    };
+    for _ in 0..REFINEMENT_STEPS {
+        window.duration =
+            transfer_duration(from, to, window.departure_radius, window.arrival_radius);
+        // The target has to be far enough ahead that it arrives at the far side of the transfer
+        // at the same time as the ship does
+        let required_phase = to.angular_speed().mul_add(-window.duration, 180.);
+        let phase_change = (required_phase - current_phase) * relative_speed.signum();
+        window.wait = phase_change.rem_euclid(360.) / relative_speed.abs();
+        window.wait = window.wait.rem_euclid(synodic_period);
+
+        window.departure_angle = from.angular_speed().mul_add(window.wait, from.angle());
+        window.departure_radius = from.radius_at(window.departure_angle);
+        window.arrival_radius = to.radius_at(window.departure_angle + 180.);
+    }
+    Some(window)
+}
+
+/// The last two bodies the player selected, in order
+#[derive(Resource, Debug, Default)]
+pub struct TransferSelection {
+    pub from: Option<Entity>,
+    pub to: Option<Entity>,
+}
+
+#[derive(Component, Debug)]
+struct TransferLabel;
+
+fn spawn_transfer_label(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Transfer Label"),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.,
+                color: OVERLAY_COLOR,
+                ..Default::default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            left: Val::Px(10.),
+            ..Default::default()
+        }),
+        TransferLabel,
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn clear_transfer_selection(mut selection: ResMut<TransferSelection>) {
+    *selection = TransferSelection::default();
+}
+
+fn track_transfer_selection(
+    mut selection: ResMut<TransferSelection>,
+    changed_query: Query<(Entity, &PickSelection), (Changed<PickSelection>, With<Orbit>)>,
+    selection_query: Query<&PickSelection, With<Orbit>>,
+) {
+    for (entity, picked) in &changed_query {
+        if picked.is_selected && selection.to != Some(entity) {
+            selection.from = selection.to;
+            selection.to = Some(entity);
+        }
+    }
+    // Deselecting everything starts the sequence over
+    if !changed_query.is_empty() && !selection_query.iter().any(|picked| picked.is_selected) {
+        *selection = TransferSelection::default();
+    }
+}
+
+/// Returns the orbit of the body around the sun, following moons up to their planet
+fn heliocentric<'a>(
+    entity: Entity,
+    orbit_query: &'a Query<(&Orbit, Option<&Parent>)>,
+) -> Option<(Entity, &'a Orbit)> {
+    let (orbit, parent) = orbit_query.get(entity).ok()?;
+    parent
+        .and_then(|parent| heliocentric(parent.get(), orbit_query))
+        .or(Some((entity, orbit)))
+}
+
+/// Returns the next transfer window between the selected bodies, if they orbit the sun separately
+fn selected_window(
+    selection: &TransferSelection,
+    orbit_query: &Query<(&Orbit, Option<&Parent>)>,
+) -> Option<TransferWindow> {
+    let (from_body, from) = heliocentric(selection.from?, orbit_query)?;
+    let (to_body, to) = heliocentric(selection.to?, orbit_query)?;
+    if from_body == to_body {
+        return None;
+    }
+    next_transfer_window(from, to)
+}
+
+fn draw_transfer_overlay(
+    selection: Res<TransferSelection>,
+    settings: Res<Settings>,
+    orbit_query: Query<(&Orbit, Option<&Parent>)>,
+    mut gizmos: Gizmos,
+) {
+    let Some(window) = selected_window(&selection, &orbit_query) else {
+        return;
+    };
+    // The transfer is worked out at true scale, so it has to be drawn in whichever view the
+    // planets are
+    let view = settings.distance_view;
+    #[allow(clippy::cast_precision_loss)]
+    let points: Vec<Vec2> = (0..=OVERLAY_SEGMENTS)
+        .map(|segment| view.view_position(window.point(segment as f32 / OVERLAY_SEGMENTS as f32)))
+        .collect();
+    // Skip every other segment to get a dashed line
+    for pair in points.windows(2).step_by(2) {
+        gizmos.line_2d(pair[0], pair[1], OVERLAY_COLOR);
+    }
+    gizmos.circle_2d(
+        view.view_position(window.point(0.)),
+        view.view_distance(window.departure_radius) * 0.02,
+        OVERLAY_COLOR,
+    );
+    gizmos.circle_2d(
+        view.view_position(window.point(1.)),
+        view.view_distance(window.arrival_radius) * 0.02,
+        OVERLAY_COLOR,
+    );
+}
+
+fn update_transfer_label(
+    selection: Res<TransferSelection>,
+    orbit_query: Query<(&Orbit, Option<&Parent>)>,
+    name_query: Query<&Name>,
+    mut label_query: Query<&mut Text, With<TransferLabel>>,
+) {
+    let Ok(mut text) = label_query.get_single_mut() else {
+        return;
+    };
+    let names = selection
+        .from
+        .zip(selection.to)
+        .and_then(|(from, to)| name_query.get(from).ok().zip(name_query.get(to).ok()));
+    text.sections[0].value = match (selected_window(&selection, &orbit_query), names) {
+        (Some(window), Some((from, to))) => format!(
+            "{from} to {to}: next transfer window in {}s, transfer takes {}s",
+            format_number(window.wait.round()),
+            format_number(window.duration.round())
+        ),
+        _ => String::new(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Degrees the outer orbit below has to be ahead of the inner one when the transfer starts
+    fn required_phase(inner: &Orbit, outer: &Orbit) -> f32 {
+        let duration = transfer_duration(inner, outer, inner.radius(), outer.radius());
+        outer.angular_speed().mul_add(-duration, 180.)
+    }
+
+    fn orbits(phase: f32) -> (Orbit, Orbit) {
+        let inner = Orbit::circle(100., 100.).starting_at(30.);
+        let outer = Orbit::circle(200., 200.);
+        let required = required_phase(&inner, &outer);
+        (inner, outer.starting_at(30. + required + phase))
+    }
+
+    #[test]
+    fn waits_for_the_phase_to_close() {
+        // The outer orbit falls behind by 1.8 degrees a second
+        let (inner, outer) = orbits(18.);
+        let window = next_transfer_window(&inner, &outer).expect("Orbits change phase");
+        assert!((window.wait - 10.).abs() < 0.01);
+        assert!((window.departure_angle - 66.).abs() < 0.01);
+    }
+
+    #[test]
+    fn waits_for_the_next_window_once_it_has_passed() {
+        let (inner, outer) = orbits(-18.);
+        let window = next_transfer_window(&inner, &outer).expect("Orbits change phase");
+        // A full synodic period of 200 seconds, less the 10 seconds since the window
+        assert!((window.wait - 190.).abs() < 0.01);
+    }
+
+    #[test]
+    fn no_window_between_orbits_with_equal_periods() {
+        let inner = Orbit::circle(100., 100.);
+        let outer = Orbit::circle(200., 100.).starting_at(90.);
+        assert_eq!(next_transfer_window(&inner, &outer), None);
+    }
+
+    #[test]
+    fn transfer_arc_runs_from_departure_to_the_far_side() {
+        let (inner, outer) = orbits(0.);
+        let window = next_transfer_window(&inner, &outer).expect("Orbits change phase");
+        assert!(window.wait.abs() < 0.01 || (window.wait - 200.).abs() < 0.01);
+        let departure = Vec2::from_angle(window.departure_angle.to_radians()) * 100.;
+        assert!(window.point(0.).distance(departure) < 0.01);
+        assert!(window.point(1.).distance(-departure * 2.) < 0.01);
+        // Halfway round an ellipse with a semi-major axis of 150 and an eccentricity of a third
+        assert!((window.point(0.5).length() - 400. / 3.).abs() < 0.01);
+    }
+}
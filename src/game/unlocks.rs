@@ -3,8 +3,19 @@ use convert_case::{Case, Casing};
 use derive_more::derive::Display;
 use serde::Deserialize;
 
+use crate::screen::Screen;
+
+use super::notifications::{NotificationCategory, Notify};
+
 pub(super) fn plugin(app: &mut App) {
     app.insert_resource(TechUnlocks::default());
+    app.init_resource::<AnnouncedTechs>();
+    app.add_systems(OnEnter(Screen::Playing), reset_announced_techs);
+    app.add_systems(
+        Update,
+        announce_unlocks
+            .run_if(in_state(Screen::Playing).and_then(resource_changed::<TechUnlocks>)),
+    );
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Display, Deserialize)]
@@ -109,4 +120,33 @@ impl TechUnlocks {
             false
         }
     }
+
+    /// Iterates over every technology unlocked so far
+    pub fn unlocked(&self) -> impl Iterator<Item = Technology> + '_ {
+        self.techs.iter().copied()
+    }
+}
+
+/// Technologies the player has already been told about this game
+#[derive(Resource, Debug, Default)]
+struct AnnouncedTechs(HashSet<Technology>);
+
+/// Anything already unlocked when a game starts isn't news
+fn reset_announced_techs(tech: Res<TechUnlocks>, mut announced: ResMut<AnnouncedTechs>) {
+    announced.0 = tech.unlocked().collect();
+}
+
+fn announce_unlocks(
+    tech: Res<TechUnlocks>,
+    mut announced: ResMut<AnnouncedTechs>,
+    mut commands: Commands,
+) {
+    for unlocked in tech.unlocked() {
+        if announced.0.insert(unlocked) {
+            commands.trigger(Notify::new(
+                NotificationCategory::Research,
+                format!("{} unlocked", unlocked.to_formatted_string()),
+            ));
+        }
+    }
 }
@@ -0,0 +1,116 @@
+//! Upgrade tiers for built harvesters, raising their output along with their power draw.
+
+use bevy::prelude::*;
+
+use super::{
+    maintenance::BuildCost,
+    resources::{HarvestedResources, PoweredStructure, ProducingStructure, RawResourceType},
+    unlocks::{TechUnlocks, Technology},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(upgrade_harvester);
+}
+
+pub struct HarvesterTier {
+    /// Output as a multiple of a newly built harvester's
+    pub output: f32,
+    /// Power draw as a multiple of a newly built harvester's
+    pub power: f32,
+    pub cost: &'static [(f32, RawResourceType)],
+    pub tech: Technology,
+}
+
+/// Every harvester starts at the first tier
+static TIERS: [HarvesterTier; 4] = [
+    HarvesterTier {
+        output: 1.,
+        power: 1.,
+        cost: &[],
+        tech: Technology::None,
+    },
+    HarvesterTier {
+        output: 2.,
+        power: 1.5,
+        cost: &[
+            (250., RawResourceType::Metals),
+            (200., RawResourceType::Silicate),
+        ],
+        tech: Technology::None,
+    },
+    HarvesterTier {
+        output: 3.5,
+        power: 2.25,
+        cost: &[
+            (600., RawResourceType::Metals),
+            (500., RawResourceType::Silicate),
+            (50., RawResourceType::Hydrogen),
+        ],
+        tech: Technology::SurfaceMineralDecomposition,
+    },
+    HarvesterTier {
+        output: 5.5,
+        power: 3.5,
+        cost: &[
+            (1_500., RawResourceType::Metals),
+            (1_200., RawResourceType::Silicate),
+            (150., RawResourceType::Hydrogen),
+            (100., RawResourceType::Oxygen),
+        ],
+        tech: Technology::DeepCrustMining,
+    },
+];
+
+/// The tier a harvester has been upgraded to, starting from 0
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Deref)]
+pub struct UpgradeLevel(usize);
+
+impl UpgradeLevel {
+    /// Returns the tier this harvester is currently at
+    pub fn tier(self) -> &'static HarvesterTier {
+        &TIERS[self.0]
+    }
+
+    /// Returns the tier this harvester would be upgraded to next, if there is one
+    pub fn next_tier(self) -> Option<&'static HarvesterTier> {
+        TIERS.get(self.0 + 1)
+    }
+}
+
+/// Trigger this event to upgrade a harvester to its next tier
+#[derive(Event, Debug)]
+pub struct UpgradeHarvester {
+    pub harvester: Entity,
+}
+
+fn upgrade_harvester(
+    trigger: Trigger<UpgradeHarvester>,
+    tech: Res<TechUnlocks>,
+    mut resources: ResMut<HarvestedResources>,
+    mut harvester_query: Query<(
+        &mut UpgradeLevel,
+        &mut ProducingStructure,
+        &mut PoweredStructure,
+        Option<&mut BuildCost>,
+    )>,
+) {
+    let Ok((mut level, mut producing, mut powered, build_cost)) =
+        harvester_query.get_mut(trigger.event().harvester)
+    else {
+        return;
+    };
+    let Some(next) = level.next_tier() else {
+        return;
+    };
+    if !tech.check(next.tech) || !resources.spend(next.cost) {
+        return;
+    }
+    // Decommissioning refunds part of everything spent on the harvester, upgrades included
+    if let Some(mut build_cost) = build_cost {
+        build_cost.add(next.cost);
+    }
+    let current = &TIERS[level.0];
+    producing.produced *= next.output / current.output;
+    powered.0 *= next.power / current.power;
+    level.0 += 1;
+}
@@ -5,17 +5,12 @@ mod screen;
 mod ui;
 mod utils;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-    core_pipeline::bloom::BloomSettings,
-    prelude::*,
-};
+use bevy::{asset::AssetMetaCheck, prelude::*};
 use bevy_mod_picking::{
     backends::raycast::{bevy_mod_raycast::prelude::RaycastVisibility, RaycastBackendSettings},
     DefaultPickingPlugins,
 };
-use bevy_pancam::{PanCam, PanCamPlugin};
+use bevy_pancam::{DirectionKeys, PanCam, PanCamPlugin};
 use sickle_ui::SickleUiPlugin;
 
 pub struct AppPlugin;
@@ -51,12 +46,6 @@ impl Plugin for AppPlugin {
                     }
                     .into(),
                     ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
                 }),
         );
 
@@ -133,7 +122,11 @@ fn spawn_camera(mut commands: Commands) {
         // [ui node outlines](https://bevyengine.org/news/bevy-0-14/#ui-node-outline-gizmos)
         // for debugging. So it's good to have this here for future-proofing.
         IsDefaultUiCamera,
-        PanCam::default(),
-        BloomSettings::default(),
+        PanCam {
+            // The arrow keys step between planets instead
+            move_keys: DirectionKeys::wasd(),
+            ..Default::default()
+        },
+        // Bloom is added by the settings, since it can be turned off
     ));
 }
@@ -1,40 +1,234 @@
 //! A loading screen during which game assets are loaded.
 //! This reduces stuttering, especially for audio on WASM.
 
-use bevy::prelude::*;
+use bevy::{asset::LoadState, ecs::system::SystemParam, prelude::*};
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
-    ui::prelude::*,
+    game::assets::{AudioManifestHandle, HandleMap, ImageKey, LoadProgress, SfxKey, SoundtrackKey},
+    ui::{multi_progress_bar::MultiProgressBar, palette::NEGATIVE_TEXT, prelude::*},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), enter_loading);
+
+    app.register_type::<LoadingAction>();
     app.add_systems(
         Update,
-        continue_to_title.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        (
+            continue_to_title.run_if(all_assets_loaded),
+            update_load_progress,
+            report_load_errors,
+            handle_loading_action,
+        )
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
+static LOADED_COLOR: Color = Color::srgb(0.3, 0.8, 0.3);
+static FAILED_COLOR: Color = Color::srgb(0.9, 0.3, 0.2);
+static PENDING_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum LoadingAction {
+    /// Carry on without the assets that failed, which just won't play
+    ContinueAnyway,
+}
+
+#[derive(Component, Debug)]
+struct LoadingBar;
+
+#[derive(Component, Debug)]
+struct LoadingCounts;
+
+#[derive(Component, Debug)]
+struct LoadErrors;
+
+/// Everything the loading screen waits on
+#[derive(SystemParam)]
+struct LoadingAssets<'w> {
+    asset_server: Res<'w, AssetServer>,
+    manifest: Res<'w, AudioManifestHandle>,
+    image_handles: Res<'w, HandleMap<ImageKey>>,
+    sfx_handles: Res<'w, HandleMap<SfxKey>>,
+    soundtrack_handles: Res<'w, HandleMap<SoundtrackKey>>,
+}
+
+impl LoadingAssets<'_> {
+    fn progress(&self) -> LoadProgress {
+        let mut progress = LoadProgress::default();
+        progress.count(&self.asset_server, &self.manifest.0);
+        self.image_handles.tally(&self.asset_server, &mut progress);
+        self.sfx_handles.tally(&self.asset_server, &mut progress);
+        self.soundtrack_handles
+            .tally(&self.asset_server, &mut progress);
+        progress
+    }
+
+    fn manifest_loaded(&self) -> bool {
+        matches!(
+            self.asset_server.get_load_state(&self.manifest.0),
+            Some(LoadState::Loaded)
+        )
+    }
+
+    /// A description of everything that went wrong loading
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(LoadState::Failed(error)) = self.asset_server.get_load_state(&self.manifest.0) {
+            errors.push(error.to_string());
+        }
+        if self.manifest_loaded() {
+            errors.extend(
+                self.sfx_handles
+                    .missing_keys()
+                    .map(|key| format!("No sound effect for {key:?} in the audio manifest")),
+            );
+            errors.extend(
+                self.soundtrack_handles
+                    .missing_keys()
+                    .map(|key| format!("No soundtrack for {key:?} in the audio manifest")),
+            );
+        }
+        errors.extend(self.image_handles.load_errors(&self.asset_server));
+        errors.extend(self.sfx_handles.load_errors(&self.asset_server));
+        errors.extend(self.soundtrack_handles.load_errors(&self.asset_server));
+        errors
+    }
+}
+
 fn enter_loading(mut commands: Commands) {
+    let mut bar = Entity::PLACEHOLDER;
     commands
         .ui_root()
         .insert(StateScoped(Screen::Loading))
         .with_children(|children| {
             children.label("Loading...");
+            bar = children
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(500.),
+                        height: Val::Px(20.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .id();
+            children.spawn((
+                Name::new("Loading Counts"),
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.,
+                        ..Default::default()
+                    },
+                ),
+                LoadingCounts,
+            ));
+            children.spawn((
+                Name::new("Load Errors"),
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.,
+                        color: NEGATIVE_TEXT,
+                        ..Default::default()
+                    },
+                )
+                .with_style(Style {
+                    max_width: Val::Px(800.),
+                    ..Default::default()
+                }),
+                LoadErrors,
+            ));
+            children
+                .button("Continue Anyway")
+                .insert((LoadingAction::ContinueAnyway, Visibility::Hidden));
         });
+    let bar = MultiProgressBar::spawn(
+        commands.entity(bar),
+        vec![
+            (0., LOADED_COLOR),
+            (0., FAILED_COLOR),
+            (100., PENDING_COLOR),
+        ],
+    );
+    commands.entity(bar).insert(LoadingBar);
+}
+
+fn all_assets_loaded(assets: LoadingAssets) -> bool {
+    let progress = assets.progress();
+    progress.pending == 0
+        && progress.failed == 0
+        && assets.sfx_handles.missing_keys().next().is_none()
+        && assets.soundtrack_handles.missing_keys().next().is_none()
+}
+
+/// Fill the bar with how much has loaded and failed, and show the counts under it
+#[allow(clippy::cast_precision_loss)]
+fn update_load_progress(
+    assets: LoadingAssets,
+    mut bar_query: Query<&mut MultiProgressBar, With<LoadingBar>>,
+    mut counts_query: Query<&mut Text, With<LoadingCounts>>,
+) {
+    let progress = assets.progress();
+    let total = progress.total().max(1) as f32;
+    for mut bar in &mut bar_query {
+        let values = bar.get_values_mut();
+        values[0] = 100. * progress.loaded as f32 / total;
+        values[1] = 100. * progress.failed as f32 / total;
+        values[2] = 100. * progress.pending as f32 / total;
+    }
+    for mut text in &mut counts_query {
+        text.sections[0].value = format!(
+            "{} loaded, {} failed, {} pending",
+            progress.loaded, progress.failed, progress.pending
+        );
+    }
+}
+
+/// List anything that couldn't be loaded, instead of sitting on the loading screen forever
+fn report_load_errors(
+    assets: LoadingAssets,
+    mut reported: Local<Vec<String>>,
+    mut errors_query: Query<&mut Text, With<LoadErrors>>,
+    mut button_query: Query<&mut Visibility, With<LoadingAction>>,
+) {
+    let errors = assets.errors();
+    if errors == *reported {
+        return;
+    }
+
+    for error in &errors {
+        error!("{error}");
+    }
+    for mut text in &mut errors_query {
+        text.sections[0].value = errors.join("\n");
+    }
+    // The game runs without any of its sounds, so even a broken manifest shouldn't hold it up
+    let can_continue = !errors.is_empty();
+    for mut visibility in &mut button_query {
+        *visibility = if can_continue {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    *reported = errors;
 }
 
-fn all_assets_loaded(
-    asset_server: Res<AssetServer>,
-    image_handles: Res<HandleMap<ImageKey>>,
-    sfx_handles: Res<HandleMap<SfxKey>>,
-    soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-) -> bool {
-    image_handles.all_loaded(&asset_server)
-        && sfx_handles.all_loaded(&asset_server)
-        && soundtrack_handles.all_loaded(&asset_server)
+fn handle_loading_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    button_query: InteractionQuery<&LoadingAction>,
+) {
+    for (interaction, action) in &button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                LoadingAction::ContinueAnyway => next_screen.set(Screen::Title),
+            }
+        }
+    }
 }
 
 fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
@@ -2,7 +2,9 @@
 
 mod credits;
 mod loading;
+mod paused;
 mod playing;
+mod settings;
 mod splash;
 mod title;
 
@@ -11,6 +13,10 @@ use bevy::prelude::*;
 pub fn plugin(app: &mut App) {
     app.init_state::<Screen>();
     app.enable_state_scoped_entities::<Screen>();
+    app.add_sub_state::<Gameplay>();
+    app.enable_state_scoped_entities::<Gameplay>();
+    app.init_state::<SettingsMenu>();
+    app.enable_state_scoped_entities::<SettingsMenu>();
 
     app.add_plugins((
         splash::plugin,
@@ -18,6 +24,8 @@ pub fn plugin(app: &mut App) {
         title::plugin,
         credits::plugin,
         playing::plugin,
+        paused::plugin,
+        settings::plugin,
     ));
 }
 
@@ -31,3 +39,21 @@ pub enum Screen {
     Credits,
     Playing,
 }
+
+/// Whether the game is running or paused, while on the [`Screen::Playing`] screen.
+/// Simulation systems should only run in [`Gameplay::Running`].
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum Gameplay {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Whether the settings menu is open over the current screen.
+#[derive(States, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SettingsMenu {
+    #[default]
+    Closed,
+    Open,
+}
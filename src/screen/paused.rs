@@ -0,0 +1,166 @@
+//! The pause menu shown over the game, which stops the simulation while it's open.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::FocusPolicy};
+use bevy_pancam::PanCam;
+
+use super::{Gameplay, Screen, SettingsMenu};
+use crate::{game::save::SaveGame, ui::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Gameplay::Paused), (enter_paused, spawn_pause_menu));
+    app.add_systems(OnExit(Gameplay::Paused), exit_paused);
+
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        (
+            handle_pause_action,
+            // Escape closes the settings menu first when it's open on top
+            resume.run_if(
+                input_just_pressed(KeyCode::Escape).and_then(in_state(SettingsMenu::Closed)),
+            ),
+        )
+            .run_if(in_state(Gameplay::Paused)),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    Settings,
+    Save,
+    Quit,
+    ConfirmQuit,
+    CancelQuit,
+}
+
+/// The main pause menu buttons, hidden while asking to confirm quitting
+#[derive(Component, Debug)]
+struct PauseButtons;
+
+#[derive(Component, Debug)]
+struct QuitConfirmation;
+
+fn enter_paused(mut time: ResMut<Time<Virtual>>, mut pancam_query: Query<&mut PanCam>) {
+    time.pause();
+    for mut pancam in &mut pancam_query {
+        pancam.enabled = false;
+    }
+}
+
+fn exit_paused(mut time: ResMut<Time<Virtual>>, mut pancam_query: Query<&mut PanCam>) {
+    time.unpause();
+    for mut pancam in &mut pancam_query {
+        pancam.enabled = true;
+    }
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Pause Menu"),
+            StateScoped(Gameplay::Paused),
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            // Keep clicks from reaching the planets and UI underneath
+            FocusPolicy::Block,
+            ZIndex::Global(100),
+        ))
+        .with_children(|children| {
+            children.header("Paused");
+            children
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(10.),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    PauseButtons,
+                ))
+                .with_children(|children| {
+                    children.button("Resume").insert(PauseAction::Resume);
+                    children.button("Settings").insert(PauseAction::Settings);
+                    children.button("Save").insert(PauseAction::Save);
+                    children.button("Quit to Title").insert(PauseAction::Quit);
+                });
+            children
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(10.),
+                            display: Display::None,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    QuitConfirmation,
+                ))
+                .with_children(|children| {
+                    children.label("Quit to the title screen? This game will be lost.");
+                    children.button("Quit").insert(PauseAction::ConfirmQuit);
+                    children.button("Cancel").insert(PauseAction::CancelQuit);
+                });
+        });
+}
+
+/// Act on pause menu buttons, swapping the menu for a confirmation before quitting
+fn handle_pause_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_gameplay: ResMut<NextState<Gameplay>>,
+    mut next_settings: ResMut<NextState<SettingsMenu>>,
+    button_query: InteractionQuery<&PauseAction>,
+    mut menu_query: Query<&mut Style, With<PauseButtons>>,
+    mut confirmation_query: Query<&mut Style, (With<QuitConfirmation>, Without<PauseButtons>)>,
+) {
+    for (interaction, action) in &button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let confirming = match action {
+            PauseAction::Resume => {
+                next_gameplay.set(Gameplay::Running);
+                continue;
+            }
+            PauseAction::Settings => {
+                next_settings.set(SettingsMenu::Open);
+                continue;
+            }
+            PauseAction::Save => {
+                commands.trigger(SaveGame);
+                continue;
+            }
+            PauseAction::ConfirmQuit => {
+                next_screen.set(Screen::Title);
+                continue;
+            }
+            PauseAction::Quit => true,
+            PauseAction::CancelQuit => false,
+        };
+        for mut style in &mut menu_query {
+            style.display = if confirming {
+                Display::None
+            } else {
+                Display::Flex
+            };
+        }
+        for mut style in &mut confirmation_query {
+            style.display = if confirming {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+}
+
+fn resume(mut next_gameplay: ResMut<NextState<Gameplay>>) {
+    next_gameplay.set(Gameplay::Running);
+}
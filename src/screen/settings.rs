@@ -0,0 +1,259 @@
+//! The settings menu, opened over the title screen or the pause menu.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::FocusPolicy};
+use sickle_ui::prelude::*;
+
+use super::SettingsMenu;
+use crate::{
+    game::settings::Settings,
+    ui::{palette::LABEL_TEXT, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(SettingsMenu::Open), spawn_settings_menu);
+    app.add_systems(OnExit(SettingsMenu::Open), save_settings);
+
+    app.register_type::<SettingsAction>();
+    app.add_systems(
+        Update,
+        (
+            handle_settings_action,
+            update_settings_sliders,
+            update_bloom_setting,
+            close_settings.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(SettingsMenu::Open)),
+    );
+}
+
+/// The UI scales the UI scale button steps through
+static UI_SCALES: [f32; 4] = [0.75, 1., 1.25, 1.5];
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    FlareDensity,
+    DistanceView,
+    UiScale,
+    Back,
+}
+
+/// A slider for one of the numeric settings
+#[derive(Component, Debug, Clone, Copy)]
+enum SettingsSlider {
+    Master,
+    Music,
+    Sfx,
+    BloomIntensity,
+    CameraFlightTime,
+}
+
+impl SettingsSlider {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Master => "Master Volume",
+            Self::Music => "Music Volume",
+            Self::Sfx => "Effects Volume",
+            Self::BloomIntensity => "Bloom Intensity",
+            Self::CameraFlightTime => "Camera Flight Seconds",
+        }
+    }
+
+    const fn max(self) -> f32 {
+        match self {
+            Self::Master | Self::Music | Self::Sfx => 1.,
+            Self::BloomIntensity => 0.5,
+            Self::CameraFlightTime => 3.,
+        }
+    }
+
+    const fn value_mut(self, settings: &mut Settings) -> &mut f32 {
+        match self {
+            Self::Master => &mut settings.master_volume,
+            Self::Music => &mut settings.music_volume,
+            Self::Sfx => &mut settings.sfx_volume,
+            Self::BloomIntensity => &mut settings.bloom_intensity,
+            Self::CameraFlightTime => &mut settings.camera_flight_time,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct BloomToggle;
+
+fn ui_scale_text(settings: &Settings) -> String {
+    format!("{:.0}%", settings.ui_scale * 100.)
+}
+
+fn spawn_settings_menu(mut commands: Commands, mut settings: ResMut<Settings>) {
+    let mut panel = Entity::PLACEHOLDER;
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Settings Menu"),
+            StateScoped(SettingsMenu::Open),
+            BackgroundColor(Color::BLACK.with_alpha(0.8)),
+            // Keep clicks from reaching whichever menu it was opened from
+            FocusPolicy::Block,
+            ZIndex::Global(200),
+        ))
+        .with_children(|children| {
+            children.header("Settings");
+            panel = children
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(500.),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(6.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .id();
+            option_row(
+                children,
+                "Flare Particles",
+                settings.flare_density.to_string(),
+                SettingsAction::FlareDensity,
+            );
+            option_row(
+                children,
+                "Distances",
+                settings.distance_view.to_string(),
+                SettingsAction::DistanceView,
+            );
+            option_row(
+                children,
+                "UI Scale",
+                ui_scale_text(&settings),
+                SettingsAction::UiScale,
+            );
+            children.button("Back").insert(SettingsAction::Back);
+        });
+
+    let mut panel = commands.ui_builder(panel);
+    for slider in [
+        SettingsSlider::Master,
+        SettingsSlider::Music,
+        SettingsSlider::Sfx,
+    ] {
+        spawn_slider(&mut panel, slider, &mut settings);
+    }
+    panel
+        .checkbox(String::from("Bloom"), settings.bloom)
+        .insert(BloomToggle);
+    spawn_slider(&mut panel, SettingsSlider::BloomIntensity, &mut settings);
+    spawn_slider(&mut panel, SettingsSlider::CameraFlightTime, &mut settings);
+}
+
+/// Spawn a labelled button that steps through the choices for a setting
+fn option_row(children: &mut ChildBuilder, label: &str, value: String, action: SettingsAction) {
+    children
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(500.),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|row| {
+            row.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 24.,
+                    color: LABEL_TEXT,
+                    ..Default::default()
+                },
+            ));
+            row.button(value).insert(action);
+        });
+}
+
+fn spawn_slider(panel: &mut UiBuilder<Entity>, slider: SettingsSlider, settings: &mut Settings) {
+    // Sliders can't start outside their range, so rein in anything edited by hand
+    let value = slider.value_mut(settings);
+    *value = value.clamp(0., slider.max());
+    panel
+        .slider(SliderConfig::horizontal(
+            String::from(slider.label()),
+            0.,
+            slider.max(),
+            *value,
+            true,
+        ))
+        .insert(slider);
+}
+
+fn handle_settings_action(
+    mut settings: ResMut<Settings>,
+    mut next_menu: ResMut<NextState<SettingsMenu>>,
+    button_query: InteractionQuery<(&SettingsAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, (action, children)) in &button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let text = match action {
+            SettingsAction::FlareDensity => {
+                settings.flare_density = settings.flare_density.next();
+                settings.flare_density.to_string()
+            }
+            SettingsAction::DistanceView => {
+                settings.distance_view = settings.distance_view.next();
+                settings.distance_view.to_string()
+            }
+            SettingsAction::UiScale => {
+                // Step up to the next scale, wrapping around to the smallest after the largest
+                settings.ui_scale = UI_SCALES
+                    .iter()
+                    .copied()
+                    .find(|scale| *scale > settings.ui_scale + f32::EPSILON)
+                    .unwrap_or(UI_SCALES[0]);
+                ui_scale_text(&settings)
+            }
+            SettingsAction::Back => {
+                next_menu.set(SettingsMenu::Closed);
+                continue;
+            }
+        };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut button_text) = texts.fetch_next() {
+            button_text.sections[0].value.clone_from(&text);
+        }
+    }
+}
+
+fn update_settings_sliders(
+    mut settings: ResMut<Settings>,
+    slider_query: Query<(&Slider, &SettingsSlider), Changed<Slider>>,
+) {
+    for (slider, setting) in &slider_query {
+        // Only write back real changes, so the settings aren't reapplied every frame
+        let value = setting.value_mut(settings.bypass_change_detection());
+        if (*value - slider.value()).abs() > f32::EPSILON {
+            *setting.value_mut(&mut settings) = slider.value();
+        }
+    }
+}
+
+fn update_bloom_setting(
+    mut settings: ResMut<Settings>,
+    checkbox_query: Query<&Checkbox, (Changed<Checkbox>, With<BloomToggle>)>,
+) {
+    for checkbox in &checkbox_query {
+        if settings.bloom != checkbox.checked {
+            settings.bloom = checkbox.checked;
+        }
+    }
+}
+
+fn close_settings(mut next_menu: ResMut<NextState<SettingsMenu>>) {
+    next_menu.set(SettingsMenu::Closed);
+}
+
+fn save_settings(settings: Res<Settings>) {
+    settings.save();
+}
@@ -2,7 +2,7 @@
 
 use bevy::prelude::*;
 
-use super::Screen;
+use super::{Screen, SettingsMenu};
 use crate::ui::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -16,6 +16,7 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Component)]
 enum TitleAction {
     Play,
+    Settings,
     Credits,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
@@ -28,6 +29,7 @@ fn enter_title(mut commands: Commands) {
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
             children.button("Play").insert(TitleAction::Play);
+            children.button("Settings").insert(TitleAction::Settings);
             children.button("Credits").insert(TitleAction::Credits);
 
             #[cfg(not(target_family = "wasm"))]
@@ -37,6 +39,7 @@ fn enter_title(mut commands: Commands) {
 
 fn handle_title_action(
     mut next_screen: ResMut<NextState<Screen>>,
+    mut next_settings: ResMut<NextState<SettingsMenu>>,
     mut button_query: InteractionQuery<&TitleAction>,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
 ) {
@@ -44,6 +47,7 @@ fn handle_title_action(
         if matches!(interaction, Interaction::Pressed) {
             match action {
                 TitleAction::Play => next_screen.set(Screen::Playing),
+                TitleAction::Settings => next_settings.set(SettingsMenu::Open),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
 
                 #[cfg(not(target_family = "wasm"))]
@@ -32,8 +32,9 @@ fn apply_interaction_palette(
     }
 }
 
+/// Only buttons make a sound, not everything that tracks the cursor like tooltips do
 fn trigger_interaction_sfx(
-    mut interactions: Query<&Interaction, Changed<Interaction>>,
+    mut interactions: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
     mut commands: Commands,
 ) {
     for interaction in &mut interactions {
@@ -0,0 +1,340 @@
+//! An overview of the whole solar system in the corner of the screen, showing where every body is
+//! along its orbit, which way flares are heading and what the camera's looking at.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::RelativeCursorPosition};
+use bevy_mod_picking::{prelude::PickSelection, selection::NoDeselect};
+
+use crate::{
+    game::{
+        camera::{CameraTarget, FlyTo},
+        flare::FlareFronts,
+        navigation::SelectBody,
+        planets::{DistanceView, Orbit, Planet},
+        settings::Settings,
+        spawn::planets::LAST_PLANET_DISTANCE,
+        sun::Sun,
+    },
+    screen::{Gameplay, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Playing), spawn_minimap);
+    app.add_systems(
+        Update,
+        (
+            add_minimap_bodies,
+            (
+                update_minimap_bodies,
+                update_minimap_flares,
+                update_minimap_viewport,
+            ),
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        (
+            toggle_minimap.run_if(input_just_pressed(KeyCode::KeyM)),
+            handle_minimap_click,
+        )
+            .run_if(in_state(Gameplay::Running)),
+    );
+}
+
+/// Width and height of the minimap in pixels
+static MINIMAP_SIZE: f32 = 220.;
+/// Clicking this close to a body in pixels selects it, rather than moving the camera there
+static CLICK_RADIUS: f32 = 6.;
+static SUN_COLOR: Color = Color::srgb(1., 0.75, 0.1);
+static ORBIT_COLOR: Color = Color::srgba(1., 1., 1., 0.15);
+static FLARE_COLOR: Color = Color::srgba(1., 0.6, 0.2, 0.8);
+static VIEWPORT_COLOR: Color = Color::srgba(1., 1., 1., 0.7);
+
+#[derive(Component, Debug)]
+pub struct Minimap;
+
+/// The dot standing in for a body on the minimap
+#[derive(Component, Debug)]
+struct MinimapBody {
+    body: Entity,
+    size: f32,
+}
+
+/// The ring a planet follows round the minimap
+#[derive(Component, Debug)]
+struct MinimapOrbit(Entity);
+
+/// A line from the sun out to where a flare has got to
+#[derive(Component, Debug)]
+struct MinimapFlare;
+
+/// The part of the solar system the camera can currently see
+#[derive(Component, Debug)]
+struct MinimapViewport;
+
+/// World units covered by each pixel of the minimap, which always fits the whole solar system
+fn units_per_pixel(view: DistanceView) -> f32 {
+    view.view_distance(*LAST_PLANET_DISTANCE) * 2.2 / MINIMAP_SIZE
+}
+
+/// The sun's position on the minimap
+fn minimap_centre() -> Vec2 {
+    Vec2::splat(MINIMAP_SIZE / 2.)
+}
+
+/// Where a point in the world is on the minimap, in pixels from its top left corner
+fn to_minimap(position: Vec2, view: DistanceView) -> Vec2 {
+    minimap_centre() + Vec2::new(position.x, -position.y) / units_per_pixel(view)
+}
+
+fn to_world(minimap: Vec2, view: DistanceView) -> Vec2 {
+    let offset = (minimap - minimap_centre()) * units_per_pixel(view);
+    Vec2::new(offset.x, -offset.y)
+}
+
+/// An absolutely positioned node of the provided size, centred on a point on the minimap
+fn centred_style(centre: Vec2, size: Vec2) -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        left: Val::Px(centre.x - size.x / 2.),
+        top: Val::Px(centre.y - size.y / 2.),
+        width: Val::Px(size.x),
+        height: Val::Px(size.y),
+        ..Default::default()
+    }
+}
+
+fn spawn_minimap(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Minimap"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.),
+                    left: Val::Px(10.),
+                    width: Val::Px(MINIMAP_SIZE),
+                    height: Val::Px(MINIMAP_SIZE),
+                    border: UiRect::all(Val::Px(1.)),
+                    overflow: Overflow::clip(),
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.with_alpha(0.6).into(),
+                border_color: ORBIT_COLOR.into(),
+                ..Default::default()
+            },
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            Minimap,
+            NoDeselect,
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        border: UiRect::all(Val::Px(1.)),
+                        ..centred_style(minimap_centre(), Vec2::ZERO)
+                    },
+                    border_color: VIEWPORT_COLOR.into(),
+                    z_index: ZIndex::Local(2),
+                    ..Default::default()
+                },
+                MinimapViewport,
+                NoDeselect,
+            ));
+        });
+}
+
+/// Give each body a dot, and each planet its orbit, as they're spawned
+fn add_minimap_bodies(
+    body_query: Query<
+        (Entity, Option<&Planet>, Option<&Handle<ColorMaterial>>),
+        Or<(Added<Planet>, Added<Sun>)>,
+    >,
+    materials: Res<Assets<ColorMaterial>>,
+    minimap_query: Query<Entity, With<Minimap>>,
+    mut commands: Commands,
+) {
+    let Ok(minimap) = minimap_query.get_single() else {
+        return;
+    };
+    for (entity, planet, material) in &body_query {
+        // The sun's material is far too bright to draw as it is
+        let (size, color) = planet.map_or((8., SUN_COLOR), |planet| {
+            let color = material
+                .and_then(|material| materials.get(material))
+                .map_or(Color::WHITE, |material| material.color);
+            (if planet.is_moon { 3. } else { 5. }, color)
+        });
+        commands.entity(minimap).with_children(|parent| {
+            if planet.is_some_and(|planet| !planet.is_moon) {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            border: UiRect::all(Val::Px(1.)),
+                            ..Default::default()
+                        },
+                        border_color: ORBIT_COLOR.into(),
+                        border_radius: BorderRadius::MAX,
+                        ..Default::default()
+                    },
+                    MinimapOrbit(entity),
+                    NoDeselect,
+                ));
+            }
+            parent.spawn((
+                NodeBundle {
+                    style: centred_style(Vec2::ZERO, Vec2::splat(size)),
+                    background_color: color.into(),
+                    border_radius: BorderRadius::MAX,
+                    z_index: ZIndex::Local(1),
+                    ..Default::default()
+                },
+                MinimapBody { body: entity, size },
+                NoDeselect,
+            ));
+        });
+    }
+}
+
+fn update_minimap_bodies(
+    settings: Res<Settings>,
+    body_query: Query<(&GlobalTransform, Option<&Orbit>)>,
+    mut dot_query: Query<(Entity, &MinimapBody, &mut Style), Without<MinimapOrbit>>,
+    mut orbit_query: Query<(Entity, &MinimapOrbit, &mut Style), Without<MinimapBody>>,
+    mut commands: Commands,
+) {
+    let view = settings.distance_view;
+    for (dot, body, mut style) in &mut dot_query {
+        let Ok((transform, _)) = body_query.get(body.body) else {
+            commands.entity(dot).despawn_recursive();
+            continue;
+        };
+        let centre = to_minimap(transform.translation().xy(), view);
+        style.left = Val::Px(centre.x - body.size / 2.);
+        style.top = Val::Px(centre.y - body.size / 2.);
+    }
+    for (ring, planet, mut style) in &mut orbit_query {
+        let Ok((_, Some(orbit))) = body_query.get(planet.0) else {
+            commands.entity(ring).despawn_recursive();
+            continue;
+        };
+        let diameter = 2. * view.view_distance(orbit.radius()) / units_per_pixel(view);
+        let border = style.border;
+        *style = Style {
+            border,
+            ..centred_style(minimap_centre(), Vec2::splat(diameter))
+        };
+    }
+}
+
+/// Keep one line for each flare, reaching from the sun to as far as it's got
+fn update_minimap_flares(
+    settings: Res<Settings>,
+    fronts: Res<FlareFronts>,
+    minimap_query: Query<Entity, With<Minimap>>,
+    mut line_query: Query<(Entity, &mut Style, &mut Transform), With<MinimapFlare>>,
+    mut commands: Commands,
+) {
+    let Ok(minimap) = minimap_query.get_single() else {
+        return;
+    };
+    let view = settings.distance_view;
+    let mut lines = line_query.iter_mut();
+    for front in &fronts.0 {
+        let length = view.view_distance(front.distance) / units_per_pixel(view);
+        let direction = Vec2::from_angle(front.angle);
+        let centre = minimap_centre() + Vec2::new(direction.x, -direction.y) * length / 2.;
+        let style = centred_style(centre, Vec2::new(length, 2.));
+        // UI nodes turn about their centre, and the minimap's y axis points down
+        let transform = Transform::from_rotation(Quat::from_rotation_z(-front.angle));
+        if let Some((_, mut line_style, mut line_transform)) = lines.next() {
+            *line_style = style;
+            line_transform.rotation = transform.rotation;
+        } else {
+            commands.entity(minimap).with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style,
+                        transform,
+                        background_color: FLARE_COLOR.into(),
+                        ..Default::default()
+                    },
+                    MinimapFlare,
+                    NoDeselect,
+                ));
+            });
+        }
+    }
+    for (line, ..) in lines {
+        commands.entity(line).despawn_recursive();
+    }
+}
+
+fn update_minimap_viewport(
+    settings: Res<Settings>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+    mut viewport_query: Query<&mut Style, With<MinimapViewport>>,
+) {
+    let Ok((transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let view = settings.distance_view;
+    let centre = to_minimap(transform.translation.xy(), view);
+    // Never shrink the rectangle out of sight when zoomed right in
+    let size = (projection.area.size() / units_per_pixel(view)).max(Vec2::splat(4.));
+    for mut style in &mut viewport_query {
+        let border = style.border;
+        *style = Style {
+            border,
+            ..centred_style(centre, size)
+        };
+    }
+}
+
+fn toggle_minimap(mut minimap_query: Query<&mut Visibility, With<Minimap>>) {
+    for mut visibility in &mut minimap_query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// Clicking on or right next to a body selects it, and clicking anywhere else moves the camera
+/// there
+fn handle_minimap_click(
+    settings: Res<Settings>,
+    minimap_query: Query<
+        (&Interaction, &RelativeCursorPosition),
+        (Changed<Interaction>, With<Minimap>),
+    >,
+    body_query: Query<(Entity, &GlobalTransform), With<PickSelection>>,
+    dot_query: Query<&MinimapBody>,
+    camera_query: Query<&OrthographicProjection, With<Camera>>,
+    mut commands: Commands,
+) {
+    for (interaction, cursor) in &minimap_query {
+        let (Interaction::Pressed, Some(normalized)) = (interaction, cursor.normalized) else {
+            continue;
+        };
+        let view = settings.distance_view;
+        let clicked = to_world(normalized * MINIMAP_SIZE, view);
+        let nearest = dot_query
+            .iter()
+            .filter_map(|dot| body_query.get(dot.body).ok())
+            .map(|(body, transform)| (body, transform.translation().xy().distance(clicked)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, distance)| *distance <= CLICK_RADIUS * units_per_pixel(view));
+        if let Some((body, _)) = nearest {
+            commands.trigger(SelectBody(body));
+        } else if let Ok(projection) = camera_query.get_single() {
+            commands.trigger(FlyTo {
+                target: CameraTarget::Point(clicked),
+                scale: projection.scale,
+            });
+        }
+    }
+}
@@ -4,10 +4,14 @@
 #![allow(dead_code, unused_imports)]
 
 pub mod interaction;
+pub mod minimap;
 pub mod multi_progress_bar;
+pub mod notification_ui;
 pub mod palette;
 pub mod planet_ui;
+pub mod power_ui;
 pub mod resource_ui;
+pub mod tooltip;
 mod widgets;
 
 pub mod prelude {
@@ -23,8 +27,12 @@ use bevy::prelude::*;
 pub fn plugin(app: &mut App) {
     app.add_plugins((
         interaction::plugin,
+        minimap::plugin,
         planet_ui::plugin,
+        power_ui::plugin,
+        notification_ui::plugin,
         resource_ui::plugin,
         multi_progress_bar::plugin,
+        tooltip::plugin,
     ));
 }
@@ -0,0 +1,403 @@
+use bevy::{
+    input::{
+        common_conditions::input_just_pressed,
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    prelude::*,
+    utils::HashSet,
+};
+use bevy_mod_picking::selection::NoDeselect;
+
+use crate::{
+    game::{
+        decay::Decay,
+        navigation::SelectBody,
+        notifications::{NotificationCategory, NotificationLog, Notify},
+    },
+    screen::Screen,
+};
+
+use super::{
+    palette::{BUTTON_PALETTE, BUTTON_PALETTE_DISABLED},
+    prelude::{InteractionPalette, InteractionQuery},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LogFilter>();
+    app.add_systems(
+        OnEnter(Screen::Playing),
+        (reset_log_filter, spawn_toast_container, spawn_log_panel),
+    );
+    app.observe(spawn_toast);
+    app.add_systems(
+        Update,
+        (
+            toggle_log_panel.run_if(input_just_pressed(KeyCode::KeyN)),
+            handle_filter_buttons,
+            handle_notification_links,
+            (update_filter_buttons, update_log_entries, scroll_log).chain(),
+            fade_toasts,
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Seconds a toast stays on screen before it has completely faded out
+static TOAST_DURATION: f32 = 5.;
+/// The most toasts shown at once, older ones are dropped to make room
+static MAX_TOASTS: usize = 5;
+/// Pixels the log moves for each line scrolled
+static SCROLL_LINE_HEIGHT: f32 = 20.;
+
+/// The categories currently shown in the log panel
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct LogFilter(HashSet<NotificationCategory>);
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self(NotificationCategory::ALL.into_iter().collect())
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct ToastContainer;
+
+#[derive(Component, Debug)]
+pub struct Toast;
+
+#[derive(Component, Debug)]
+pub struct LogPanel;
+
+/// The part of the log that moves as it's scrolled, clipped by the `LogPanel` around it
+#[derive(Component, Debug, Default)]
+pub struct LogEntries {
+    offset: f32,
+}
+
+#[derive(Component, Debug)]
+pub struct FilterButton(NotificationCategory);
+
+/// Pressing this selects the planet the notification was about
+#[derive(Component, Debug)]
+pub struct NotificationLink(Option<Entity>);
+
+fn reset_log_filter(mut filter: ResMut<LogFilter>) {
+    *filter = LogFilter::default();
+}
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Toasts"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(11.),
+                left: Val::Percent(35.),
+                width: Val::Percent(30.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ToastContainer,
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn spawn_log_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Notification Log"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.),
+                    left: Val::Percent(30.),
+                    width: Val::Percent(40.),
+                    height: Val::Percent(30.),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(6.)),
+                    row_gap: Val::Px(4.),
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.with_alpha(0.6).into(),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            LogPanel,
+            NoDeselect,
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "LOG (N to close)",
+                    TextStyle {
+                        font_size: 18.,
+                        ..Default::default()
+                    },
+                ),
+                NoDeselect,
+            ));
+            spawn_filter_buttons(parent);
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_grow: 1.,
+                            overflow: Overflow::clip_y(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    Interaction::default(),
+                    NoDeselect,
+                ))
+                .with_children(|clip| {
+                    clip.spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.),
+                                flex_direction: FlexDirection::Column,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        LogEntries::default(),
+                        NoDeselect,
+                    ));
+                });
+        });
+}
+
+/// Spawn a row with a toggle for each notification category
+fn spawn_filter_buttons(parent: &mut ChildBuilder) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                column_gap: Val::Px(4.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|row| {
+            for category in NotificationCategory::ALL {
+                row.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::horizontal(Val::Px(4.)),
+                            ..Default::default()
+                        },
+                        background_color: BUTTON_PALETTE.none.into(),
+                        ..Default::default()
+                    },
+                    BUTTON_PALETTE.clone(),
+                    FilterButton(category),
+                    NoDeselect,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        TextBundle::from_section(
+                            category.to_string(),
+                            TextStyle {
+                                font_size: 14.,
+                                color: category.color(),
+                                ..Default::default()
+                            },
+                        ),
+                        NoDeselect,
+                    ));
+                });
+            }
+        });
+}
+
+/// Spawn a button showing the notification, with the text coloured by its category
+fn spawn_notification_button(
+    parent: &mut ChildBuilder,
+    notification: &Notify,
+    background: Color,
+    extra: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::axes(Val::Px(6.), Val::Px(2.)),
+                    ..Default::default()
+                },
+                background_color: background.into(),
+                ..Default::default()
+            },
+            NotificationLink(notification.planet),
+            NoDeselect,
+            extra,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                TextBundle::from_section(
+                    format!("[{}] {}", notification.category, notification.message),
+                    TextStyle {
+                        font_size: 14.,
+                        color: notification.category.color(),
+                        ..Default::default()
+                    },
+                ),
+                NoDeselect,
+            ));
+        });
+}
+
+fn spawn_toast(
+    trigger: Trigger<Notify>,
+    container_query: Query<(Entity, Option<&Children>), With<ToastContainer>>,
+    mut commands: Commands,
+) {
+    let Ok((container, toasts)) = container_query.get_single() else {
+        return;
+    };
+    // Make room for the new toast by dropping the oldest ones
+    let toasts = toasts.map_or(&[][..], |children| &children[..]);
+    let excess = (toasts.len() + 1).saturating_sub(MAX_TOASTS);
+    for toast in &toasts[..excess] {
+        commands.entity(*toast).despawn_recursive();
+    }
+    commands.entity(container).with_children(|parent| {
+        spawn_notification_button(
+            parent,
+            trigger.event(),
+            Color::BLACK.with_alpha(0.6),
+            (Toast, Decay::new(TOAST_DURATION)),
+        );
+    });
+}
+
+/// Fade toasts out over their lifetime, after which `Decay` cleans them up
+fn fade_toasts(
+    mut toast_query: Query<(&Decay, &mut BackgroundColor, &Children), With<Toast>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (decay, mut background, children) in &mut toast_query {
+        let alpha = 1. - decay.fraction();
+        background.0.set_alpha(0.6 * alpha);
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            for section in &mut text.sections {
+                section.style.color.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+fn toggle_log_panel(mut panel_query: Query<&mut Visibility, With<LogPanel>>) {
+    for mut visibility in &mut panel_query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn handle_filter_buttons(
+    mut filter: ResMut<LogFilter>,
+    mut button_query: InteractionQuery<&FilterButton>,
+) {
+    for (interaction, button) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) && !filter.remove(&button.0) {
+            filter.insert(button.0);
+        }
+    }
+}
+
+fn update_filter_buttons(
+    filter: Res<LogFilter>,
+    mut button_query: Query<(&FilterButton, &mut InteractionPalette, &mut BackgroundColor)>,
+) {
+    if !filter.is_changed() {
+        return;
+    }
+    for (button, mut palette, mut background) in &mut button_query {
+        *palette = if filter.contains(&button.0) {
+            BUTTON_PALETTE.clone()
+        } else {
+            BUTTON_PALETTE_DISABLED.clone()
+        };
+        *background = palette.none.into();
+    }
+}
+
+/// Rebuild the log whenever something new is added to it or the filter changes
+fn update_log_entries(
+    log: Res<NotificationLog>,
+    filter: Res<LogFilter>,
+    entries_query: Query<Entity, With<LogEntries>>,
+    mut commands: Commands,
+) {
+    if !log.is_changed() && !filter.is_changed() {
+        return;
+    }
+    let Ok(entries) = entries_query.get_single() else {
+        return;
+    };
+    commands
+        .entity(entries)
+        .despawn_descendants()
+        .with_children(|parent| {
+            // Newest first, so the latest news is what's in view without scrolling
+            for notification in log
+                .entries
+                .iter()
+                .rev()
+                .filter(|notification| filter.contains(&notification.category))
+            {
+                spawn_notification_button(parent, notification, Color::NONE, ());
+            }
+        });
+}
+
+fn scroll_log(
+    mut wheel_events: EventReader<MouseWheel>,
+    clip_query: Query<(&Interaction, &Node), Without<LogEntries>>,
+    mut entries_query: Query<(&mut LogEntries, &mut Style, &Node, &Parent)>,
+) {
+    let scrolled: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    for (mut entries, mut style, node, parent) in &mut entries_query {
+        let Ok((interaction, clip)) = clip_query.get(parent.get()) else {
+            continue;
+        };
+        let hovered = !matches!(interaction, Interaction::None);
+        let max_offset = (node.size().y - clip.size().y).max(0.);
+        let offset = if hovered {
+            entries.offset - scrolled
+        } else {
+            entries.offset
+        };
+        // Keep it in range even when not scrolling, since filtering can shrink the list
+        let offset = offset.clamp(0., max_offset);
+        if (offset - entries.offset).abs() > f32::EPSILON {
+            entries.offset = offset;
+            style.top = Val::Px(-offset);
+        }
+    }
+}
+
+/// Select the planet a notification was about when it's clicked, from a toast or the log
+fn handle_notification_links(
+    link_query: InteractionQuery<&NotificationLink>,
+    mut commands: Commands,
+) {
+    for (interaction, link) in &link_query {
+        if let (Interaction::Pressed, Some(planet)) = (interaction, link.0) {
+            commands.trigger(SelectBody(planet));
+        }
+    }
+}
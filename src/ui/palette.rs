@@ -10,6 +10,9 @@ pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
 pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
 pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
+pub const FLARE_TEXT: Color = Color::srgb(1., 0.6, 0.2);
+pub const POSITIVE_TEXT: Color = Color::srgb(0.6, 1., 0.6);
+pub const NEGATIVE_TEXT: Color = Color::srgb(1., 0.5, 0.4);
 
 pub const NODE_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
 
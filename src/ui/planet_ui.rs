@@ -5,35 +5,69 @@ use sickle_ui::{prelude::*, widgets::layout::column};
 
 use crate::{
     game::{
+        harvesters::{self, harvester_cost, BuyHarvester},
         highlight::LinkSelectionObject,
-        planets::PlanetNameLabel,
+        logistics::{HomeWorld, LocalStockpile, LogisticsSettings, ToggleRoute, TransportRoute},
+        maintenance::{DecommissionHarvester, Wear},
+        planets::{Atmosphere, Planet, PlanetNameLabel},
+        population::{BuildHabitat, Population},
+        power::{CyclePowerPriority, PowerPriority, PowerSupply},
         resources::{
-            self, update_planet_ui_resource_bar, BuiltHarvesters, HarvestedResources,
-            PlanetResourceLabel, PlanetResources, RawResourceType, ResourceBarTextLabel,
-            ResourceCostLabel,
+            update_planet_ui_resource_bar, BuiltHarvesters, EnabledStructure, HarvestedResources,
+            PlanetResourceLabel, PlanetResources, ProducingStructure, RawResourceType,
+            ResourceBarTextLabel, ResourceCostLabel, StationType,
         },
         sun::Sun,
         unlocks::{TechUnlocks, Technology},
+        upgrades::{UpgradeHarvester, UpgradeLevel},
     },
     screen::Screen,
-    ui::palette::BUTTON_PALETTE,
+    ui::palette::{
+        BUTTON_PALETTE, BUTTON_PALETTE_DISABLED, FLARE_TEXT, HEADER_TEXT, LABEL_TEXT,
+        NEGATIVE_TEXT, POSITIVE_TEXT,
+    },
     utils::format_number,
 };
 
 use super::{
     multi_progress_bar::MultiProgressBar,
     palette::{BUTTON_HOVERED_BACKGROUND, BUTTON_PRESSED_BACKGROUND, NODE_BACKGROUND},
-    prelude::InteractionPalette,
+    prelude::{InteractionPalette, InteractionQuery},
+    tooltip::Tooltip,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         PreUpdate,
-        (spawn_ui, update_ui_name, update_buy_button)
+        (
+            spawn_ui,
+            update_ui_name,
+            update_population_labels,
+            update_logistics_ui,
+            update_buy_button,
+            update_harvester_list,
+            update_upgrade_rows,
+            update_priority_buttons,
+            update_deposit_tooltips,
+            update_harvester_tooltips,
+        )
             .chain()
             .before(update_planet_ui_resource_bar)
             .run_if(in_state(Screen::Playing)),
     );
+    app.add_systems(
+        Update,
+        (
+            handle_buy_button,
+            handle_habitat_button,
+            handle_route_button,
+            handle_route_destination_button,
+            handle_upgrade_button,
+            handle_decommission_button,
+            handle_priority_button,
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
 }
 
 #[derive(Component, Debug)]
@@ -42,18 +76,96 @@ pub struct PlanetUI(Entity);
 #[derive(Component, Debug)]
 pub struct ResourceHolderLabel;
 
+#[derive(Component, Debug)]
+pub struct PopulationLabel(Entity);
+
+#[derive(Component, Debug)]
+pub struct HabitatCostLabel(Entity);
+
+#[derive(Component, Debug)]
+pub struct BuildHabitatButton(Entity);
+
+#[derive(Component, Debug)]
+pub struct StockpileLabel(Entity);
+
+/// The route a row of route buttons starts or stops, with the destination the player has picked
+#[derive(Component, Debug)]
+pub struct RouteRow {
+    from: Entity,
+    to: Entity,
+    resource: RawResourceType,
+}
+
+#[derive(Component, Debug)]
+pub struct RouteButton(Entity);
+
+/// Cycles the destination of a route row through every other planet with a stockpile
+#[derive(Component, Debug)]
+pub struct RouteDestinationButton(Entity);
+
+/// Holds a row for every harvester built on the planet, rebuilt whenever another one is built
+#[derive(Component, Debug)]
+pub struct HarvesterList {
+    planet: Entity,
+    shown: usize,
+}
+
+#[derive(Component, Debug)]
+pub struct HarvesterTierLabel(Entity);
+
+#[derive(Component, Debug)]
+pub struct UpgradeCostLabel(Entity);
+
+#[derive(Component, Debug)]
+pub struct UpgradeButton(Entity);
+
+#[derive(Component, Debug)]
+pub struct DecommissionButton(Entity);
+
+#[derive(Component, Debug)]
+pub struct PriorityButton(Entity);
+
+/// UI that is only shown while logistics mode is enabled
+#[derive(Component, Debug)]
+pub struct LogisticsOnly;
+
 // FIXME: Resolve the too many lines issue by factoring this out
 // TODO: Probably consider creating a custom UI widget to handle this
 #[allow(clippy::too_many_lines)]
 fn spawn_ui(
     mut commands: Commands,
     existing_ui_query: Query<(Entity, &PlanetUI)>,
-    selected_planet_query: Query<(&PickSelection, &PlanetResources, Entity), With<PickSelection>>,
+    selected_planet_query: Query<
+        (
+            &PickSelection,
+            &PlanetResources,
+            Option<&Planet>,
+            Has<Population>,
+            Has<LocalStockpile>,
+            Entity,
+        ),
+        With<PickSelection>,
+    >,
+    home_query: Query<Entity, With<HomeWorld>>,
+    depot_query: Query<Entity, Or<(With<HomeWorld>, With<LocalStockpile>)>>,
 ) {
-    if let Some((_, planet_resources, planet_entity)) = selected_planet_query
-        .iter()
-        .find(|(selection, _, _)| selection.is_selected)
+    if let Some((_, planet_resources, planet, has_population, has_stockpile, planet_entity)) =
+        selected_planet_query
+            .iter()
+            .find(|(selection, _, _, _, _, _)| selection.is_selected)
     {
+        // Only planets with a stockpile can ship anything, and they start out shipping home, or
+        // to the next planet along from home
+        let destination = if depot_query.contains(planet_entity) {
+            home_query
+                .get_single()
+                .ok()
+                .filter(|home| *home != planet_entity)
+                .or_else(|| next_destination(planet_entity, planet_entity, &depot_query))
+        } else {
+            None
+        };
+
         // If there is already UI built for that planet, then exit, otherwise remove the prev UI
         if let Ok((prev_entity, prev_ui)) = existing_ui_query.get_single() {
             if prev_ui.0 == planet_entity {
@@ -83,6 +195,28 @@ fn spawn_ui(
                     })
                     .style()
                     .justify_content(JustifyContent::Center);
+                if has_population {
+                    spawn_population_rows(column, planet_entity);
+                }
+                if has_stockpile {
+                    column
+                        .row(|stockpile_row| {
+                            stockpile_row.spawn((
+                                TextBundle::from_section(
+                                    "Stockpile",
+                                    TextStyle {
+                                        font_size: 12.,
+                                        ..Default::default()
+                                    },
+                                ),
+                                StockpileLabel(planet_entity),
+                                NoDeselect,
+                            ));
+                        })
+                        .insert(LogisticsOnly)
+                        .style()
+                        .justify_content(JustifyContent::Center);
+                }
                 // Just to provide some vertical space between the name and the resources
                 column
                     .row(|spacer_row| {
@@ -109,6 +243,26 @@ fn spawn_ui(
                                     ));
                                 });
 
+                                // Display the station type and whether flares can reach it
+                                column.row(|station_row| {
+                                    let station = resource.station_type();
+                                    let exposure = shielding(planet, station).map_or_else(
+                                        || "exposed to flares".to_string(),
+                                        |shield| format!("shielded by {shield}"),
+                                    );
+                                    station_row.spawn((
+                                        TextBundle::from_section(
+                                            format!("{station} station, {exposure}"),
+                                            TextStyle {
+                                                font_size: 12.,
+                                                ..Default::default()
+                                            },
+                                        ),
+                                        station_tooltip(station, planet),
+                                        NoDeselect,
+                                    ));
+                                });
+
                                 // Display text to acompany the progress bar
                                 column
                                     .row(|bar_text_row| {
@@ -122,6 +276,7 @@ fn spawn_ui(
                                             ),
                                             PlanetResourceLabel(planet_entity, resource.name()),
                                             ResourceBarTextLabel,
+                                            NoDeselect,
                                         ));
                                     })
                                     .style()
@@ -171,6 +326,7 @@ fn spawn_ui(
                                                         resource.name(),
                                                     ),
                                                     ResourceCostLabel(RawResourceType::Metals),
+                                                    cost_tooltip(resource.station_type()),
                                                     NoDeselect,
                                                 ));
                                             });
@@ -202,6 +358,7 @@ fn spawn_ui(
                                                         resource.name(),
                                                     ),
                                                     ResourceCostLabel(RawResourceType::Silicate),
+                                                    cost_tooltip(resource.station_type()),
                                                     NoDeselect,
                                                 ));
                                             });
@@ -260,6 +417,14 @@ fn spawn_ui(
                                         .width(Val::Percent(100.))
                                         .justify_content(JustifyContent::Center);
                                 });
+                                if let Some(destination) = destination {
+                                    spawn_route_row(
+                                        column,
+                                        planet_entity,
+                                        destination,
+                                        resource.name(),
+                                    );
+                                }
                                 column
                                     .row(|spacer_row| {
                                         spacer_row.spawn(NodeBundle::default());
@@ -267,6 +432,10 @@ fn spawn_ui(
                                     .style()
                                     .min_height(Val::Vh(1.));
                             }
+                            column.column(|_| {}).insert(HarvesterList {
+                                planet: planet_entity,
+                                shown: 0,
+                            });
                         });
                     })
                     .style()
@@ -290,6 +459,206 @@ fn spawn_ui(
     }
 }
 
+/// Spawn the population label and the button to build more habitats
+fn spawn_population_rows(column: &mut UiBuilder<Entity>, planet_entity: Entity) {
+    column
+        .row(|population_row| {
+            population_row.spawn((
+                TextBundle::from_section(
+                    "Population",
+                    TextStyle {
+                        font_size: 16.,
+                        ..Default::default()
+                    },
+                ),
+                PopulationLabel(planet_entity),
+                NoDeselect,
+            ));
+        })
+        .style()
+        .justify_content(JustifyContent::Center);
+    column.row(|habitat_row| {
+        habitat_row.column(|cost_col| {
+            cost_col.spawn((
+                TextBundle::from_section(
+                    "Cost",
+                    TextStyle {
+                        font_size: 10.,
+                        ..Default::default()
+                    },
+                ),
+                HabitatCostLabel(planet_entity),
+                NoDeselect,
+            ));
+        });
+        habitat_row
+            .column(|button_col| {
+                button_col
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                justify_content: JustifyContent::Center,
+                                ..Default::default()
+                            },
+                            background_color: BUTTON_PALETTE.clone().none.into(),
+                            ..Default::default()
+                        },
+                        BUTTON_PALETTE.clone(),
+                        BuildHabitatButton(planet_entity),
+                        NoDeselect,
+                    ))
+                    .entity_commands()
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "Build Habitat",
+                                TextStyle {
+                                    font_size: 18.,
+                                    ..Default::default()
+                                },
+                            ),
+                            NoDeselect,
+                        ));
+                    });
+            })
+            .style()
+            .width(Val::Percent(100.))
+            .justify_content(JustifyContent::Center);
+    });
+}
+
+/// Spawn the label, upgrade cost and buttons for a built harvester
+fn spawn_upgrade_row(column: &mut UiBuilder<Entity>, harvester: Entity) {
+    column.row(|upgrade_row| {
+        upgrade_row.column(|label_col| {
+            label_col.spawn((
+                TextBundle::from_section(
+                    "Harvester",
+                    TextStyle {
+                        font_size: 12.,
+                        ..Default::default()
+                    },
+                ),
+                HarvesterTierLabel(harvester),
+                NoDeselect,
+            ));
+            label_col.spawn((
+                TextBundle::from_section(
+                    "Cost",
+                    TextStyle {
+                        font_size: 10.,
+                        ..Default::default()
+                    },
+                ),
+                UpgradeCostLabel(harvester),
+                NoDeselect,
+            ));
+        });
+        upgrade_row
+            .column(|button_col| {
+                spawn_harvester_button(button_col, "Upgrade", UpgradeButton(harvester));
+                spawn_harvester_button(button_col, "Priority", PriorityButton(harvester));
+                spawn_harvester_button(button_col, "Decommission", DecommissionButton(harvester));
+            })
+            .style()
+            .width(Val::Percent(100.))
+            .justify_content(JustifyContent::Center);
+    });
+}
+
+/// Spawn one of the small buttons next to a built harvester
+fn spawn_harvester_button(column: &mut UiBuilder<Entity>, text: &str, button: impl Bundle) {
+    column
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                background_color: BUTTON_PALETTE.clone().none.into(),
+                ..Default::default()
+            },
+            BUTTON_PALETTE.clone(),
+            button,
+            NoDeselect,
+        ))
+        .entity_commands()
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 14.,
+                        ..Default::default()
+                    },
+                ),
+                NoDeselect,
+            ));
+        });
+}
+
+/// Spawn the buttons that pick where to ship a resource to, and start or stop shipping it there
+fn spawn_route_row(
+    column: &mut UiBuilder<Entity>,
+    from: Entity,
+    to: Entity,
+    resource: RawResourceType,
+) {
+    column
+        .row(|route_row| {
+            let row = route_row.id();
+            route_row.insert(RouteRow { from, to, resource });
+            for (button, text) in [
+                (route_row.spawn(RouteDestinationButton(row)).id(), "To"),
+                (route_row.spawn(RouteButton(row)).id(), "Ship"),
+            ] {
+                route_row
+                    .commands()
+                    .entity(button)
+                    .insert((
+                        ButtonBundle {
+                            style: Style {
+                                justify_content: JustifyContent::Center,
+                                margin: UiRect::horizontal(Val::Px(4.)),
+                                ..Default::default()
+                            },
+                            background_color: BUTTON_PALETTE.clone().none.into(),
+                            ..Default::default()
+                        },
+                        BUTTON_PALETTE.clone(),
+                        NoDeselect,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                text,
+                                TextStyle {
+                                    font_size: 14.,
+                                    ..Default::default()
+                                },
+                            ),
+                            NoDeselect,
+                        ));
+                    });
+            }
+        })
+        .insert(LogisticsOnly)
+        .style()
+        .justify_content(JustifyContent::Center);
+}
+
+/// Returns the planet with a stockpile after `current` to ship from `from` to, wrapping around
+fn next_destination(
+    from: Entity,
+    current: Entity,
+    depot_query: &Query<Entity, Or<(With<HomeWorld>, With<LocalStockpile>)>>,
+) -> Option<Entity> {
+    let mut depots: Vec<Entity> = depot_query.iter().filter(|depot| *depot != from).collect();
+    depots.sort_unstable();
+    let first = depots.first().copied();
+    depots.into_iter().find(|depot| *depot > current).or(first)
+}
+
 fn update_ui_name(
     mut name_text_query: Query<&mut Text, With<PlanetNameLabel>>,
     selected_planet_query: Query<(&PickSelection, &Name), With<PlanetResources>>,
@@ -305,16 +674,541 @@ fn update_ui_name(
     }
 }
 
+fn update_population_labels(
+    population_query: Query<&Population>,
+    mut population_label_query: Query<(&mut Text, &PopulationLabel)>,
+    mut cost_label_query: Query<(&mut Text, &HabitatCostLabel), Without<PopulationLabel>>,
+) {
+    for (mut text, label) in &mut population_label_query {
+        if let Ok(population) = population_query.get(label.0) {
+            text.sections[0].value = format!(
+                "Population: {} / {}",
+                format_number(population.count().floor()),
+                format_number(population.cap().floor())
+            );
+        }
+    }
+    for (mut text, label) in &mut cost_label_query {
+        if let Ok(population) = population_query.get(label.0) {
+            let cost = population.habitat_cost();
+            text.sections[0].value = format!(
+                "{} Metal\n{} Silicate",
+                format_number(cost.0),
+                format_number(cost.1)
+            );
+        }
+    }
+}
+
+fn update_logistics_ui(
+    logistics: Res<LogisticsSettings>,
+    stockpile_query: Query<&LocalStockpile>,
+    route_query: Query<&TransportRoute>,
+    name_query: Query<&Name>,
+    mut logistics_only_query: Query<&mut Style, With<LogisticsOnly>>,
+    mut stockpile_label_query: Query<(&mut Text, &StockpileLabel)>,
+    route_row_query: Query<&RouteRow>,
+    route_button_query: Query<(&RouteButton, &Children)>,
+    destination_button_query: Query<(&RouteDestinationButton, &Children)>,
+    mut route_text_query: Query<&mut Text, Without<StockpileLabel>>,
+) {
+    for mut style in &mut logistics_only_query {
+        style.display = if logistics.enabled {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for (mut text, label) in &mut stockpile_label_query {
+        if let Ok(stockpile) = stockpile_query.get(label.0) {
+            text.sections[0].value = format!(
+                "Stockpile: {} Metals, {} Silicate, {} Hydrogen, {} Oxygen",
+                format_number(stockpile.metals),
+                format_number(stockpile.silicate),
+                format_number(stockpile.hydrogen),
+                format_number(stockpile.oxygen)
+            );
+        }
+    }
+    for (button, children) in &route_button_query {
+        let Ok(row) = route_row_query.get(button.0) else {
+            continue;
+        };
+        let shipping = route_query.iter().any(|route| {
+            route.from == row.from && route.to == row.to && route.resource == row.resource
+        });
+        let mut texts = route_text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = if shipping {
+                "Stop Shipping".to_string()
+            } else {
+                "Ship".to_string()
+            };
+        }
+    }
+    for (button, children) in &destination_button_query {
+        let Some(name) = route_row_query
+            .get(button.0)
+            .ok()
+            .and_then(|row| name_query.get(row.to).ok())
+        else {
+            continue;
+        };
+        let mut texts = route_text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = format!("To {name}");
+        }
+    }
+}
+
+/// Grey out buy buttons for harvesters that can't currently be built
 fn update_buy_button(
     tech: Res<TechUnlocks>,
     resources: Res<HarvestedResources>,
-    sun: Query<&Sun>,
-    planet_query: Query<(&PlanetResources, &BuiltHarvesters, &GlobalTransform)>,
-    mut button_query: Query<(&mut InteractionPalette, &PlanetResourceLabel)>,
+    planet_query: Query<(&PlanetResources, &BuiltHarvesters)>,
+    mut button_query: Query<(
+        &mut InteractionPalette,
+        &mut BackgroundColor,
+        &Interaction,
+        &PlanetResourceLabel,
+    )>,
 ) {
-    for (mut pallete, label) in &mut button_query {
-        if let Ok((resources, harvesters, transform)) = planet_query.get(label.0) {
-            if let Some(resource) = resources.get(label.1) {}
+    for (mut palette, mut background, interaction, label) in &mut button_query {
+        if let Ok((planet_resources, harvesters)) = planet_query.get(label.0) {
+            if let Some(resource) = planet_resources.get(label.1) {
+                let cost = harvester_cost(resource, harvesters, &tech);
+                *palette = if resource.is_unlocked(&tech)
+                    && resources.metals >= cost.0
+                    && resources.silicate >= cost.1
+                {
+                    BUTTON_PALETTE
+                } else {
+                    BUTTON_PALETTE_DISABLED.clone()
+                };
+                if matches!(interaction, Interaction::None) {
+                    *background = palette.none.into();
+                }
+            }
+        }
+    }
+}
+
+/// Rebuild the list of harvesters whenever another one is built on the planet
+fn update_harvester_list(
+    mut commands: Commands,
+    mut list_query: Query<(Entity, &mut HarvesterList)>,
+    planet_query: Query<&BuiltHarvesters>,
+) {
+    for (list_entity, mut list) in &mut list_query {
+        let Ok(harvesters) = planet_query.get(list.planet) else {
+            continue;
+        };
+        let mut built: Vec<Entity> = harvesters.0.values().flatten().copied().collect();
+        if built.len() == list.shown {
+            continue;
+        }
+        list.shown = built.len();
+        built.sort();
+        commands.entity(list_entity).despawn_descendants();
+        let mut column = commands.ui_builder(list_entity);
+        for harvester in built {
+            spawn_upgrade_row(&mut column, harvester);
+        }
+    }
+}
+
+/// Update the tier and upgrade cost of each listed harvester, greying out the upgrade button for
+/// any that can't currently be upgraded
+fn update_upgrade_rows(
+    tech: Res<TechUnlocks>,
+    resources: Res<HarvestedResources>,
+    harvester_query: Query<(&Name, &UpgradeLevel)>,
+    wear_query: Query<&Wear>,
+    mut tier_label_query: Query<(&mut Text, &HarvesterTierLabel)>,
+    mut cost_label_query: Query<(&mut Text, &UpgradeCostLabel), Without<HarvesterTierLabel>>,
+    mut button_query: Query<(
+        &mut InteractionPalette,
+        &mut BackgroundColor,
+        &Interaction,
+        &UpgradeButton,
+    )>,
+) {
+    for (mut text, label) in &mut tier_label_query {
+        if let Ok((name, level)) = harvester_query.get(label.0) {
+            text.sections[0].value = wear_query.get(label.0).map_or_else(
+                |_| format!("{name}: Tier {}", **level + 1),
+                |wear| {
+                    format!(
+                        "{name}: Tier {}, {}% condition",
+                        **level + 1,
+                        format_number((wear.condition() * 100.).floor())
+                    )
+                },
+            );
+        }
+    }
+    for (mut text, label) in &mut cost_label_query {
+        if let Ok((_, level)) = harvester_query.get(label.0) {
+            text.sections[0].value = match level.next_tier() {
+                None => "Fully upgraded".to_string(),
+                Some(tier) if !tech.check(tier.tech) => {
+                    format!("Requires {}", tier.tech.to_formatted_string())
+                }
+                Some(tier) => tier
+                    .cost
+                    .iter()
+                    .map(|(amount, resource)| format!("{} {resource}", format_number(*amount)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+        }
+    }
+    for (mut palette, mut background, interaction, button) in &mut button_query {
+        let Ok((_, level)) = harvester_query.get(button.0) else {
+            continue;
+        };
+        *palette = if level
+            .next_tier()
+            .is_some_and(|tier| tech.check(tier.tech) && resources.can_afford(tier.cost))
+        {
+            BUTTON_PALETTE
+        } else {
+            BUTTON_PALETTE_DISABLED.clone()
+        };
+        if matches!(interaction, Interaction::None) {
+            *background = palette.none.into();
+        }
+    }
+}
+
+fn update_priority_buttons(
+    priority_query: Query<&PowerPriority>,
+    button_query: Query<(&PriorityButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (button, children) in &button_query {
+        let Ok(priority) = priority_query.get(button.0) else {
+            continue;
+        };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = format!("{priority} Priority");
+        }
+    }
+}
+
+/// Explain what a deposit holds at each level and what has to be researched to reach it
+fn update_deposit_tooltips(
+    mut commands: Commands,
+    tech: Res<TechUnlocks>,
+    planet_query: Query<&PlanetResources>,
+    label_query: Query<(Entity, &PlanetResourceLabel, Has<Tooltip>), With<ResourceBarTextLabel>>,
+) {
+    for (entity, label, has_tooltip) in &label_query {
+        if has_tooltip && !tech.is_changed() {
+            continue;
+        }
+        let Some(resource) = planet_query
+            .get(label.0)
+            .ok()
+            .and_then(|resources| resources.get(label.1))
+        else {
+            continue;
+        };
+        let mut tooltip = Tooltip::new(format!("{} deposit", label.1))
+            .line("Consumed: already harvested")
+            .line("Available: unlocked and left to harvest")
+            .line("Unlockable: reachable with more research")
+            .line("");
+        for (amount, level_tech) in resource.levels() {
+            tooltip = tooltip.line(format!("Up to {}", format_number(*amount)));
+            tooltip = if level_tech.is_none() {
+                tooltip.span(" from the start", POSITIVE_TEXT)
+            } else {
+                tech_tooltip(tooltip.span(" with ", Color::WHITE), *level_tech, &tech)
+            };
+        }
+        commands.entity(entity).insert(tooltip);
+    }
+}
+
+/// Show how each harvester's output is worked out, and what its next upgrade needs
+fn update_harvester_tooltips(
+    mut commands: Commands,
+    tech: Res<TechUnlocks>,
+    harvester_query: Query<(
+        &Name,
+        &ProducingStructure,
+        &UpgradeLevel,
+        Option<&Wear>,
+        Option<&PowerSupply>,
+        Option<&EnabledStructure>,
+    )>,
+    mut tier_label_query: Query<
+        (Entity, &HarvesterTierLabel, Option<&mut Tooltip>),
+        Without<UpgradeCostLabel>,
+    >,
+    mut cost_label_query: Query<(Entity, &UpgradeCostLabel, Option<&mut Tooltip>)>,
+) {
+    for (entity, label, current) in &mut tier_label_query {
+        let Ok((name, producing, level, wear, supply, enabled)) = harvester_query.get(label.0)
+        else {
+            continue;
+        };
+        let condition = wear.map_or(1., Wear::condition);
+        let supply = supply.map_or(1., |supply| supply.0);
+        let mut tooltip = Tooltip::new(format!("{name} output"))
+            .line("Output = base x tier x condition x power supplied")
+            .line(format!(
+                "{} x {} x {}% x {}%",
+                format_number(harvesters::HARVESTER_OUTPUT),
+                format_number(level.tier().output),
+                format_number((condition * 100.).floor()),
+                format_number((supply * 100.).floor()),
+            ))
+            .line(format!(
+                "= {:.4} {} per tick",
+                producing.produced * condition * supply,
+                producing.res_type
+            ));
+        if enabled.is_some_and(|enabled| !enabled.0) {
+            tooltip = tooltip.line("").span(
+                "Stalled for lack of power, colonists or anything left to harvest",
+                NEGATIVE_TEXT,
+            );
+        }
+        replace_tooltip(&mut commands, entity, current, tooltip);
+    }
+    for (entity, label, current) in &mut cost_label_query {
+        let Ok((_, _, level, _, _, _)) = harvester_query.get(label.0) else {
+            continue;
+        };
+        let tooltip = level.next_tier().map_or_else(
+            || Tooltip::new("Fully upgraded"),
+            |tier| {
+                let tooltip = Tooltip::new(format!("Tier {}", **level + 2)).line(format!(
+                    "{}x output for {}x power draw",
+                    format_number(tier.output),
+                    format_number(tier.power)
+                ));
+                if tier.tech.is_none() {
+                    tooltip
+                } else {
+                    tech_tooltip(tooltip.line("Needs "), tier.tech, &tech)
+                }
+            },
+        );
+        replace_tooltip(&mut commands, entity, current, tooltip);
+    }
+}
+
+/// Give a label the provided tooltip, leaving it alone if it already has the same one so the
+/// popup isn't rebuilt every frame
+fn replace_tooltip(
+    commands: &mut Commands,
+    entity: Entity,
+    current: Option<Mut<Tooltip>>,
+    tooltip: Tooltip,
+) {
+    match current {
+        Some(mut current) => {
+            current.set_if_neq(tooltip);
+        }
+        None => {
+            commands.entity(entity).insert(tooltip);
+        }
+    }
+}
+
+/// Name a technology in a tooltip, followed by whether it has been researched, and if not, which
+/// of its prerequisites are still missing
+fn tech_tooltip(tooltip: Tooltip, tech: Technology, techs: &TechUnlocks) -> Tooltip {
+    let tooltip = tooltip.span(tech.to_formatted_string(), HEADER_TEXT);
+    if techs.check(tech) {
+        return tooltip.span(" (researched)", POSITIVE_TEXT);
+    }
+    let missing: Vec<String> = tech
+        .prerequisites()
+        .into_iter()
+        .filter(|prerequisite| !techs.check(*prerequisite))
+        .map(Technology::to_formatted_string)
+        .collect();
+    if missing.is_empty() {
+        tooltip.span(" (ready to research)", LABEL_TEXT)
+    } else {
+        tooltip.span(
+            format!(" (needs {} first)", missing.join(", ")),
+            NEGATIVE_TEXT,
+        )
+    }
+}
+
+/// Explain what a station of the provided type needs to run, and how well it's protected
+/// Returns what keeps flares from reaching stations of the provided type on a planet, if anything.
+/// The sun doesn't have a `Planet`, but it doesn't have an atmosphere or magnetic field either
+fn shielding(planet: Option<&Planet>, station: StationType) -> Option<&'static str> {
+    let planet = planet?;
+    if planet.is_exposed(station) {
+        None
+    } else if planet.has_magnetic_field {
+        Some("magnetic field")
+    } else {
+        Some("atmosphere")
+    }
+}
+
+fn station_tooltip(station: StationType, planet: Option<&Planet>) -> Tooltip {
+    let workforce = if station.workforce() > 0. {
+        format!(
+            "Needs {} colonists to run",
+            format_number(station.workforce())
+        )
+    } else {
+        "Automated, needs no colonists".to_string()
+    };
+    let atmosphere = planet.map_or(Atmosphere::None, |planet| planet.atmosphere);
+    let exposure = if planet.is_some_and(|planet| planet.has_magnetic_field) {
+        "The magnetic field keeps flare energy reaching the planet from getting through".to_string()
+    } else {
+        format!(
+            "{}% of flare energy reaching the planet gets through to it",
+            format_number((atmosphere.exposure(station) * 100.).floor())
+        )
+    };
+    Tooltip::new(format!("{station} station"))
+        .line(format!(
+            "Draws {} power",
+            format_number(station.power_draw())
+        ))
+        .line(workforce)
+        .line("")
+        .span(
+            exposure,
+            if shielding(planet, station).is_none() {
+                FLARE_TEXT
+            } else {
+                POSITIVE_TEXT
+            },
+        )
+        .line("Planets with a magnetic field deflect most flares before they arrive")
+}
+
+/// Explain where the cost of building another harvester comes from
+fn cost_tooltip(station: StationType) -> Tooltip {
+    let (metals, silicate) = station.cost();
+    Tooltip::new("Harvester cost")
+        .line(format!(
+            "A {station} station starts at {} metals and {} silicate.",
+            format_number(metals),
+            format_number(silicate)
+        ))
+        .line("Harder to reach deposits multiply that by their technology's cost modifier.")
+        .line(format!(
+            "Each harvester already built for this resource here adds another {}%.",
+            format_number(((harvesters::HARVESTER_COST_SCALING - 1.) * 100.).round())
+        ))
+}
+
+fn handle_buy_button(
+    mut commands: Commands,
+    // Labels for the resource share the component, and can track the cursor for their tooltips
+    mut button_query: Query<
+        (&Interaction, &PlanetResourceLabel),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, label) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            commands.trigger(BuyHarvester {
+                planet: label.0,
+                resource: label.1,
+            });
+        }
+    }
+}
+
+fn handle_habitat_button(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&BuildHabitatButton>,
+) {
+    for (interaction, button) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            commands.trigger(BuildHabitat { planet: button.0 });
+        }
+    }
+}
+
+fn handle_upgrade_button(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&UpgradeButton>,
+) {
+    for (interaction, button) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            commands.trigger(UpgradeHarvester {
+                harvester: button.0,
+            });
+        }
+    }
+}
+
+fn handle_decommission_button(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&DecommissionButton>,
+) {
+    for (interaction, button) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            commands.trigger(DecommissionHarvester {
+                harvester: button.0,
+            });
+        }
+    }
+}
+
+fn handle_priority_button(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&PriorityButton>,
+) {
+    for (interaction, button) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            commands.trigger(CyclePowerPriority {
+                structure: button.0,
+            });
+        }
+    }
+}
+
+fn handle_route_button(
+    mut commands: Commands,
+    mut button_query: InteractionQuery<&RouteButton>,
+    route_row_query: Query<&RouteRow>,
+) {
+    for (interaction, button) in &mut button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(row) = route_row_query.get(button.0) {
+            commands.trigger(ToggleRoute {
+                from: row.from,
+                to: row.to,
+                resource: row.resource,
+            });
+        }
+    }
+}
+
+fn handle_route_destination_button(
+    mut button_query: InteractionQuery<&RouteDestinationButton>,
+    mut route_row_query: Query<&mut RouteRow>,
+    depot_query: Query<Entity, Or<(With<HomeWorld>, With<LocalStockpile>)>>,
+) {
+    for (interaction, button) in &mut button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(mut row) = route_row_query.get_mut(button.0) {
+            if let Some(next) = next_destination(row.from, row.to, &depot_query) {
+                row.to = next;
+            }
         }
     }
 }
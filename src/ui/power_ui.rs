@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::{power::PowerGrid, resources::HarvestedResources},
+    screen::Screen,
+    utils::format_number,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Playing), spawn_power_ui);
+    app.add_systems(Update, update_power_ui.run_if(in_state(Screen::Playing)));
+}
+
+/// The most unpowered structures listed by name before the rest are just counted
+static LISTED_UNPOWERED: usize = 5;
+
+#[derive(Component, Debug)]
+pub struct PowerGridLabel;
+
+#[derive(Component, Debug)]
+pub struct UnpoweredLabel;
+
+fn spawn_power_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Power Grid UI"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(12.),
+                    left: Val::Px(10.),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.with_alpha(0.2).into(),
+                ..Default::default()
+            },
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "POWER GRID",
+                TextStyle {
+                    font_size: 18.,
+                    ..Default::default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.,
+                        ..Default::default()
+                    },
+                ),
+                PowerGridLabel,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 12.,
+                        color: Color::srgb(1., 0.5, 0.4),
+                        ..Default::default()
+                    },
+                ),
+                UnpoweredLabel,
+            ));
+        });
+}
+
+fn update_power_ui(
+    grid: Res<PowerGrid>,
+    resources: Res<HarvestedResources>,
+    name_query: Query<&Name>,
+    mut grid_label_query: Query<&mut Text, With<PowerGridLabel>>,
+    mut unpowered_label_query: Query<&mut Text, (With<UnpoweredLabel>, Without<PowerGridLabel>)>,
+) {
+    if let Ok(mut text) = grid_label_query.get_single_mut() {
+        let delivered = if grid.demand > 0. {
+            grid.delivered / grid.demand * 100.
+        } else {
+            100.
+        };
+        text.sections[0].value = format!(
+            "Supply: {}\nDemand: {}\nStored: {}\nDelivered: {}%",
+            format_number(grid.supply),
+            format_number(grid.demand),
+            format_number(resources.power),
+            format_number(delivered.floor())
+        );
+    }
+    if let Ok(mut text) = unpowered_label_query.get_single_mut() {
+        let mut lines: Vec<String> = grid
+            .unpowered
+            .iter()
+            .take(LISTED_UNPOWERED)
+            .filter_map(|entity| name_query.get(*entity).ok())
+            .map(ToString::to_string)
+            .collect();
+        if grid.unpowered.len() > LISTED_UNPOWERED {
+            lines.push(format!(
+                "and {} more",
+                grid.unpowered.len() - LISTED_UNPOWERED
+            ));
+        }
+        text.sections[0].value = if lines.is_empty() {
+            String::new()
+        } else {
+            format!("Unpowered:\n{}", lines.join("\n"))
+        };
+    }
+}
@@ -8,6 +8,8 @@ use crate::{
     screen::Screen,
 };
 
+use super::{palette::FLARE_TEXT, tooltip::Tooltip};
+
 #[derive(Debug, Event)]
 pub struct SpawnResourceUIEvent;
 
@@ -76,12 +78,20 @@ fn spawn_resource_ui(_trigger: Trigger<SpawnResourceUIEvent>, mut commands: Comm
                         ));
                     });
 
-                parent.spawn(TextBundle::from_section(
-                    "Solar Output:",
-                    TextStyle {
-                        font_size: 20.,
-                        ..Default::default()
-                    },
+                parent.spawn((
+                    TextBundle::from_section(
+                        "Solar Output:",
+                        TextStyle {
+                            font_size: 20.,
+                            ..Default::default()
+                        },
+                    ),
+                    Tooltip::new("Solar Output")
+                        .line("How hard the sun is burning, relative to its starting power.")
+                        .line("Output = relative power x (cycle + 0.5)")
+                        .line("Relative power creeps up over the whole game. As it rises, ")
+                        .span("solar arrays produce more, ", Color::WHITE)
+                        .span("and flares come more often and hit harder.", FLARE_TEXT),
                 ));
                 parent
                     .spawn(TextBundle::from_section(
@@ -92,12 +102,22 @@ fn spawn_resource_ui(_trigger: Trigger<SpawnResourceUIEvent>, mut commands: Comm
                         },
                     ))
                     .insert(SunPowerLabel);
-                parent.spawn(TextBundle::from_section(
-                    "Solar Cycle:",
-                    TextStyle {
-                        font_size: 20.,
-                        ..Default::default()
-                    },
+                parent.spawn((
+                    TextBundle::from_section(
+                        "Solar Cycle:",
+                        TextStyle {
+                            font_size: 20.,
+                            ..Default::default()
+                        },
+                    ),
+                    Tooltip::new("Solar Cycle")
+                        .line("Where the sun is in its activity cycle, 0 quietest to 1 at peak.")
+                        .line("It rises for about 33 minutes, then falls for as long again.")
+                        .line("Solar output ranges from half the sun's relative power at the ")
+                        .span(
+                            "bottom of the cycle to one and a half times it at the peak.",
+                            Color::WHITE,
+                        ),
                 ));
                 parent
                     .spawn(TextBundle::from_section(
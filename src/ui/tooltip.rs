@@ -0,0 +1,194 @@
+//! Explanations that pop up next to the cursor after hovering over a UI node for a moment.
+//!
+//! Add a `Tooltip` to any UI node to give it one, and update it like any other component if what
+//! it explains changes over time.
+
+use bevy::{prelude::*, ui::UiSystem, window::PrimaryWindow};
+
+use super::palette::HEADER_TEXT;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TooltipHover>();
+    app.add_systems(Startup, spawn_tooltip_popup);
+    app.add_systems(
+        PostUpdate,
+        (add_tooltip_interaction, track_tooltip_hover, show_tooltip)
+            .chain()
+            .before(UiSystem::Layout),
+    );
+}
+
+/// Seconds the cursor has to rest on a node before its tooltip shows
+static TOOLTIP_DELAY: f32 = 0.5;
+/// Gap in pixels between the cursor and the tooltip
+static CURSOR_OFFSET: f32 = 16.;
+
+/// Rich text shown while hovering over the node this is attached to
+#[derive(Component, Debug, Clone, Default)]
+pub struct Tooltip {
+    sections: Vec<TextSection>,
+}
+
+impl Tooltip {
+    /// Start a tooltip with a heading
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            sections: vec![TextSection::new(
+                title,
+                TextStyle {
+                    font_size: 16.,
+                    color: HEADER_TEXT,
+                    ..Default::default()
+                },
+            )],
+        }
+    }
+
+    /// Add plain text on a new line
+    #[must_use]
+    pub fn line(self, text: impl Into<String>) -> Self {
+        self.span(format!("\n{}", text.into()), Color::WHITE)
+    }
+
+    /// Add coloured text to the end of the current line
+    #[must_use]
+    pub fn span(mut self, text: impl Into<String>, color: Color) -> Self {
+        self.sections.push(TextSection::new(
+            text,
+            TextStyle {
+                font_size: 13.,
+                color,
+                ..Default::default()
+            },
+        ));
+        self
+    }
+}
+
+/// `TextSection` can't be compared, so compare what's shown instead
+impl PartialEq for Tooltip {
+    fn eq(&self, other: &Self) -> bool {
+        self.sections.len() == other.sections.len()
+            && self.sections.iter().zip(&other.sections).all(|(a, b)| {
+                a.value == b.value
+                    && a.style.color == b.style.color
+                    && a.style.font_size == b.style.font_size
+            })
+    }
+}
+
+/// The node with a tooltip currently under the cursor, and how long it's been there
+#[derive(Resource, Debug, Default)]
+struct TooltipHover {
+    target: Option<Entity>,
+    hovered_for: f32,
+}
+
+#[derive(Component, Debug)]
+struct TooltipPopup;
+
+fn spawn_tooltip_popup(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Tooltip"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    max_width: Val::Px(320.),
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.with_alpha(0.85).into(),
+                visibility: Visibility::Hidden,
+                // Tooltips go over everything else, wherever they are in the hierarchy
+                z_index: ZIndex::Global(i32::MAX),
+                ..Default::default()
+            },
+            TooltipPopup,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::default());
+        });
+}
+
+/// Nodes only track the cursor if they have an `Interaction`, which plain text and containers
+/// don't come with
+fn add_tooltip_interaction(
+    mut commands: Commands,
+    tooltip_query: Query<Entity, (Added<Tooltip>, Without<Interaction>)>,
+) {
+    for entity in &tooltip_query {
+        commands.entity(entity).insert(Interaction::default());
+    }
+}
+
+fn track_tooltip_hover(
+    time: Res<Time>,
+    mut hover: ResMut<TooltipHover>,
+    tooltip_query: Query<(Entity, &Interaction, &ViewVisibility), With<Tooltip>>,
+) {
+    let hovered = tooltip_query
+        .iter()
+        .find(|(_, interaction, visibility)| {
+            !matches!(interaction, Interaction::None) && visibility.get()
+        })
+        .map(|(entity, _, _)| entity);
+    if hovered == hover.target {
+        hover.hovered_for += time.delta_seconds();
+    } else {
+        hover.target = hovered;
+        hover.hovered_for = 0.;
+    }
+}
+
+/// Show the hovered node's tooltip next to the cursor, flipping it to the other side of the
+/// cursor rather than letting it run off the edge of the window
+fn show_tooltip(
+    hover: Res<TooltipHover>,
+    mut shown_target: Local<Option<Entity>>,
+    tooltip_query: Query<Ref<Tooltip>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    mut popup_query: Query<(&mut Style, &mut Visibility, &Node, &Children), With<TooltipPopup>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok((mut style, mut visibility, node, children)) = popup_query.get_single_mut() else {
+        return;
+    };
+    let shown = hover
+        .target
+        .filter(|_| hover.hovered_for >= TOOLTIP_DELAY)
+        .and_then(|target| tooltip_query.get(target).ok());
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| Some((window, window.cursor_position()?)));
+    let (Some(tooltip), Some((window, cursor))) = (shown, cursor) else {
+        *visibility = Visibility::Hidden;
+        *shown_target = None;
+        return;
+    };
+
+    // Only touch the text when it changes, so it doesn't have to be laid out again every frame
+    if tooltip.is_changed() || *shown_target != hover.target {
+        *shown_target = hover.target;
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections.clone_from(&tooltip.sections);
+        }
+    }
+    *visibility = Visibility::Inherited;
+
+    let size = node.size();
+    let fit = |cursor: f32, size: f32, window: f32| {
+        let position = if cursor + CURSOR_OFFSET + size > window {
+            cursor - CURSOR_OFFSET - size
+        } else {
+            cursor + CURSOR_OFFSET
+        };
+        position.clamp(0., (window - size).max(0.))
+    };
+    // The popup's size is already scaled, but the position it's given gets scaled again
+    style.left = Val::Px(fit(cursor.x, size.x, window.width()) / ui_scale.0);
+    style.top = Val::Px(fit(cursor.y, size.y, window.height()) / ui_scale.0);
+}